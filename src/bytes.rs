@@ -0,0 +1,98 @@
+use core::str::Utf8Error;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+use crate::{bad_index, Repr, SharedOwner, Src, SrcStr};
+
+pub type SrcBytes<P = Rc<Vec<u8>>> = Src<[u8], P>;
+
+// the owner's byte and text views needn't be the same buffer, so each
+// conversion is checked against the view it ends up in
+impl<P: SharedOwner<[u8]> + SharedOwner<str>> Src<[u8], P> {
+    // shares the owner, so the text keeps its position in the byte source
+    pub fn to_srcstr(&self) -> Result<SrcStr<P>, Utf8Error> {
+        let repr = match self.repr {
+            Repr::Range { start, len } => {
+                let (rc, range) = (self.rc.clone().unwrap(), start..start + len);
+                let bytes = SharedOwner::<str>::elems(&rc).get(range.clone()).unwrap_or_else(|| bad_index(range.clone()));
+                core::str::from_utf8(bytes)?;
+                let text = Src::<str, P>::from_raw_parts(rc, range.clone()).unwrap_or_else(|| bad_index(range));
+                return Ok(Src { created: self.created, ..text });
+            },
+            Repr::Static(bytes) => Repr::Static(core::str::from_utf8(bytes)?),
+        };
+        Ok(Src { rc: self.rc.clone(), repr, created: self.created })
+    }
+}
+
+impl<P: SharedOwner<[u8]> + SharedOwner<str>> From<SrcStr<P>> for SrcBytes<P> {
+    fn from(s: SrcStr<P>) -> Self {
+        let repr = match s.repr {
+            Repr::Range { start, len } => {
+                let range = start..start + len;
+                let bytes = Src::<[u8], P>::from_raw_parts(s.rc.unwrap(), range.clone()).unwrap_or_else(|| bad_index(range));
+                return Self { created: s.created, ..bytes };
+            },
+            Repr::Static(text) => Repr::Static(text.as_bytes()),
+        };
+        Self { rc: s.rc, repr, created: s.created }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_section() {
+        let mut file = b"\x00\x01\xff".to_vec();
+        file.extend_from_slice(b"Is man merely a mistake of God's?");
+        let a: SrcBytes = file.into();
+
+        let text = a.sub(3..36).to_srcstr().unwrap();
+        assert_eq!(text.range(), Some(3..36));
+        assert_eq!("mistake", &text.sub(16..23)[..]);
+        assert_eq!(text.sub(16..23).range(), Some(19..26));
+
+        assert!(a.sub(1..5).to_srcstr().is_err());
+    }
+
+    // an owner whose text view isn't its byte view
+    #[derive(Clone)]
+    struct TwoViews(Rc<(Vec<u8>, Vec<u8>)>);
+
+    unsafe impl SharedOwner<[u8]> for TwoViews {
+        fn elems(&self) -> &[u8] {
+            &self.0 .0
+        }
+    }
+
+    unsafe impl SharedOwner<str> for TwoViews {
+        fn elems(&self) -> &[u8] {
+            &self.0 .1
+        }
+    }
+
+    #[test]
+    fn checked_against_text_view() {
+        let owner = TwoViews(Rc::new((b"ascii".to_vec(), b"as\xffii".to_vec())));
+        let a = Src::<[u8], _>::from_raw_parts(owner.clone(), 0..5).unwrap();
+        assert!(a.sub(1..4).to_srcstr().is_err());
+        assert_eq!("as", &a.sub(0..2).to_srcstr().unwrap()[..]);
+
+        let short = TwoViews(Rc::new((b"ascii".to_vec(), b"as".to_vec())));
+        let b = Src::<[u8], _>::from_raw_parts(short, 0..5).unwrap();
+        assert!(std::panic::catch_unwind(|| b.to_srcstr()).is_err());
+    }
+
+    #[test]
+    fn edit() {
+        let mut a: SrcBytes = b"God is dead".to_vec().into();
+        a.edit(|s| *s = &s[7..]);
+        assert_eq!(a.range(), Some(7..11));
+
+        a.edit(|s| *s = b"alive");
+        assert_eq!(a.range(), None);
+        assert_eq!(&a[..], b"alive");
+    }
+}
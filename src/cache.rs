@@ -0,0 +1,328 @@
+use core::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::string::String;
+use std::vec::Vec;
+
+#[cfg(feature = "notify")]
+use std::sync::mpsc::{channel, Receiver};
+
+#[cfg(feature = "notify")]
+use notify::{RecursiveMode, Watcher};
+
+use crate::provenance::Provenance;
+use crate::{SharedOwner, Src, SrcStr};
+#[cfg(feature = "notify")]
+use crate::{SpanRemapper, TextEdit};
+
+// reads each file once, keyed by canonical path, so every import of the same
+// module shares one buffer
+pub struct SourceCache<P: SharedOwner<str> = Rc<String>> {
+    files: HashMap<PathBuf, SrcStr<P>>,
+    by_hash: Option<HashMap<u64, Vec<SrcStr<P>>>>, // only when deduplicating
+}
+
+impl<P: SharedOwner<str>> Default for SourceCache<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: SharedOwner<str>> SourceCache<P> {
+    pub fn new() -> Self {
+        Self { files: HashMap::new(), by_hash: None }
+    }
+
+    // files with identical contents share one owner, even under different paths
+    pub fn with_dedup() -> Self {
+        Self { files: HashMap::new(), by_hash: Some(HashMap::new()) }
+    }
+
+    fn insert(&mut self, path: PathBuf, text: String, created: Provenance) -> SrcStr<P>
+    where
+        P: From<String>,
+    {
+        let file = match &mut self.by_hash {
+            Some(by_hash) => {
+                let same = by_hash.entry(hash_of(&text)).or_default();
+                match same.iter().find(|file| ***file == *text) {
+                    Some(file) => file.clone(),
+                    None => {
+                        let file = Src { created, ..Src::whole(P::from(text)) };
+                        same.push(file.clone());
+                        file
+                    },
+                }
+            },
+            None => Src { created, ..Src::whole(P::from(text)) },
+        };
+        self.files.insert(path, file.clone());
+        file
+    }
+
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn load(&mut self, path: &Path) -> io::Result<SrcStr<P>>
+    where
+        P: From<String>,
+    {
+        let path = path.canonicalize()?;
+        if let Some(file) = self.files.get(&path) {
+            return Ok(file.clone());
+        }
+        let text = fs::read_to_string(&path)?;
+        Ok(self.insert(path, text, Provenance::here()))
+    }
+
+    #[cfg(feature = "tokio")]
+    pub async fn load_async(&mut self, path: &Path) -> io::Result<SrcStr<P>>
+    where
+        P: From<String>,
+    {
+        let path = tokio::fs::canonicalize(path).await?;
+        if let Some(file) = self.files.get(&path) {
+            return Ok(file.clone());
+        }
+        // async fns can't track their caller, so these record no provenance
+        let text = tokio::fs::read_to_string(&path).await?;
+        Ok(self.insert(path, text, Provenance::NONE))
+    }
+
+    // watches every file loaded so far; see `CacheWatcher::add` for later ones
+    #[cfg(feature = "notify")]
+    pub fn watch(&self) -> notify::Result<CacheWatcher> {
+        let (sender, events) = channel();
+        let mut watcher = CacheWatcher { watcher: notify::recommended_watcher(sender)?, events };
+        for path in self.files.keys() {
+            watcher.add(path)?;
+        }
+        Ok(watcher)
+    }
+
+    // reloads whatever changed on disk since the last poll, without blocking.
+    // reloaded files aren't deduplicated. files gone by the time they're read
+    // (saved by renaming over them, say) are skipped; any other read error
+    // is returned once the rest of the changes are in
+    #[cfg(feature = "notify")]
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn poll_changes<F>(&mut self, watcher: &CacheWatcher, on_change: F) -> io::Result<()>
+    where
+        P: From<String>,
+        F: FnMut(&Path, &SpanRemapper<P>),
+    {
+        self.reload(&watcher.events, on_change)
+    }
+
+    #[cfg(feature = "notify")]
+    #[cfg_attr(feature = "provenance", track_caller)]
+    fn reload<F>(&mut self, events: &Receiver<notify::Result<notify::Event>>, mut on_change: F) -> io::Result<()>
+    where
+        P: From<String>,
+        F: FnMut(&Path, &SpanRemapper<P>),
+    {
+        let mut failed = None;
+        while let Ok(event) = events.try_recv() {
+            let event = match event {
+                Ok(event) if event.kind.is_modify() || event.kind.is_create() => event,
+                _ => continue,
+            };
+            for path in event.paths {
+                let old = match self.files.get(&path) {
+                    Some(old) => old.clone(),
+                    None => continue,
+                };
+                let text = match fs::read_to_string(&path) {
+                    Ok(text) => text,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                    Err(err) => {
+                        failed.get_or_insert(err);
+                        continue;
+                    },
+                };
+                if *text == *old {
+                    continue;
+                }
+                let (new, remap) = old.apply_edits(&[TextEdit::between(&old, &text)]).expect("between gives a valid edit");
+                self.files.insert(path.clone(), new);
+                self.forget(&old);
+                on_change(&path, &remap);
+            }
+        }
+        failed.map_or(Ok(()), Err)
+    }
+
+    // drops a replaced file from the dedup table once no path still uses it,
+    // so later loads don't share an owner with stale contents
+    #[cfg(feature = "notify")]
+    fn forget(&mut self, old: &SrcStr<P>) {
+        if self.files.values().any(|file| file == old) {
+            return;
+        }
+        if let Some(same) = self.by_hash.as_mut().and_then(|by_hash| by_hash.get_mut(&hash_of(old))) {
+            same.retain(|file| file != old);
+        }
+    }
+
+    // only finds files that were already loaded
+    pub fn get(&self, path: &Path) -> Option<&SrcStr<P>> {
+        self.files.get(&path.canonicalize().ok()?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+fn hash_of(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(feature = "notify")]
+pub struct CacheWatcher {
+    watcher: notify::RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+}
+
+#[cfg(feature = "notify")]
+impl CacheWatcher {
+    pub fn add(&mut self, path: &Path) -> notify::Result<()> {
+        self.watcher.watch(&path.canonicalize()?, RecursiveMode::NonRecursive)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::format;
+
+    #[test]
+    fn load() {
+        let dir = std::env::temp_dir().join(format!("srcstr-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("twilight.txt");
+        fs::write(&path, "Twilight of the Idols").unwrap();
+
+        let mut cache: SourceCache = SourceCache::new();
+        let a = cache.load(&path).unwrap();
+        let b = cache.load(&dir.join(".").join("twilight.txt")).unwrap();
+
+        assert_eq!("Twilight of the Idols", &a[..]);
+        assert_eq!(a, b);
+        assert_eq!(1, cache.len());
+        assert!(cache.load(&dir.join("missing.txt")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn dedup() {
+        let dir = std::env::temp_dir().join(format!("srcstr-cache-dedup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in &["a.h", "b.h", "c.h"] {
+            fs::write(dir.join(name), if *name == "c.h" { "other" } else { "#pragma once" }).unwrap();
+        }
+
+        let mut cache: SourceCache = SourceCache::with_dedup();
+        let a = cache.load(&dir.join("a.h")).unwrap();
+        let b = cache.load(&dir.join("b.h")).unwrap();
+        let c = cache.load(&dir.join("c.h")).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        let mut plain: SourceCache = SourceCache::new();
+        assert_ne!(plain.load(&dir.join("a.h")).unwrap(), plain.load(&dir.join("b.h")).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "notify")]
+    fn modified(paths: &[&Path]) -> notify::Result<notify::Event> {
+        let event = notify::Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Any));
+        Ok(paths.iter().fold(event, |event, path| event.add_path(path.canonicalize().unwrap())))
+    }
+
+    #[cfg(feature = "notify")]
+    #[test]
+    fn watch() {
+        let dir = std::env::temp_dir().join(format!("srcstr-cache-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (path, gone) = (dir.join("main.src"), dir.join("gone.src"));
+        fs::write(&path, "fn main() { old(); }").unwrap();
+        fs::write(&gone, "fn gone() {}").unwrap();
+
+        let mut cache: SourceCache = SourceCache::new();
+        let a = cache.load(&path).unwrap();
+        let main = a.sub(3..7);
+        cache.load(&gone).unwrap();
+
+        // renamed away before the poll, listed ahead of a file that did change
+        let (sender, events) = channel();
+        sender.send(modified(&[&gone, &path])).unwrap();
+        fs::remove_file(&gone).unwrap();
+        fs::write(&path, "fn main() { new(); }").unwrap();
+
+        let mut changed = None;
+        cache.reload(&events, |_, remap| changed = remap.remap(&main)).unwrap();
+        assert_eq!(Some(3..7), changed.unwrap().range());
+        assert_eq!("fn main() { new(); }", &cache.get(&path).unwrap()[..]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "notify")]
+    #[test]
+    fn reload_forgets_stale_dedup() {
+        let dir = std::env::temp_dir().join(format!("srcstr-cache-forget-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in &["a.h", "b.h", "c.h", "d.h"] {
+            fs::write(dir.join(name), "#pragma once").unwrap();
+        }
+
+        let mut cache: SourceCache = SourceCache::with_dedup();
+        let a = cache.load(&dir.join("a.h")).unwrap();
+        assert_eq!(a, cache.load(&dir.join("b.h")).unwrap());
+        let (sender, events) = channel();
+
+        // b.h still has the old text, so it can still be shared
+        fs::write(dir.join("a.h"), "#ifndef A_H").unwrap();
+        sender.send(modified(&[&dir.join("a.h")])).unwrap();
+        cache.reload(&events, |_, _| ()).unwrap();
+        assert_eq!(a, cache.load(&dir.join("c.h")).unwrap());
+
+        fs::write(dir.join("b.h"), "#ifndef B_H").unwrap();
+        fs::write(dir.join("c.h"), "#ifndef C_H").unwrap();
+        sender.send(modified(&[&dir.join("b.h"), &dir.join("c.h")])).unwrap();
+        cache.reload(&events, |_, _| ()).unwrap();
+        assert_ne!(a, cache.load(&dir.join("d.h")).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn load_async() {
+        let dir = std::env::temp_dir().join(format!("srcstr-cache-async-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("daybreak.txt");
+        fs::write(&path, "Daybreak").unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let mut cache: SourceCache = SourceCache::new();
+        let a = runtime.block_on(cache.load_async(&path)).unwrap();
+        let b = cache.load(&path).unwrap();
+
+        assert_eq!("Daybreak", &a[..]);
+        assert_eq!(a, b);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{bad_index, SharedOwner, Src};
+
+// char offsets are relative to the slice, like byte offsets are. an owner
+// with a CharIndex (see `Indexed`) answers in O(log n), anything else scans
+impl<P: SharedOwner<str>> Src<str, P> {
+    // panics if offset is past the end or not on a char boundary
+    pub fn byte_to_char(&self, offset: usize) -> usize {
+        if !self.is_char_boundary(offset) {
+            bad_index(offset..offset);
+        }
+        match (self.src().and_then(|rc| rc.char_index()), self.src(), self.range()) {
+            (Some(index), Some(rc), Some(range)) => {
+                let bytes = rc.elems();
+                index.byte_to_char(bytes, range.start + offset) - index.byte_to_char(bytes, range.start)
+            },
+            _ => self[..offset].chars().count(),
+        }
+    }
+
+    // the byte offset of the nth char; the char count itself maps to the end
+    pub fn char_to_byte(&self, char: usize) -> Option<usize> {
+        match (self.src().and_then(|rc| rc.char_index()), self.src(), self.range()) {
+            (Some(index), Some(rc), Some(range)) => {
+                let bytes = rc.elems();
+                let before = index.byte_to_char(bytes, range.start);
+                let offset = index.char_to_byte(bytes, before + char)?;
+                (offset <= range.end).then(|| offset - range.start)
+            },
+            _ => self.char_indices().map(|(i, _)| i).chain(Some(self.len())).nth(char),
+        }
+    }
+
+    pub fn char_count(&self) -> usize {
+        self.byte_to_char(self.len())
+    }
+
+    // like sub, but counting chars rather than bytes
+    pub fn sub_chars(&self, index: core::ops::Range<usize>) -> Self {
+        let start = self.char_to_byte(index.start);
+        let end = self.char_to_byte(index.end);
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => self.sub(start..end),
+            _ => bad_index(index),
+        }
+    }
+
+    // each char with its one-char slice
+    pub fn char_indices_spanned(&self) -> impl Iterator<Item = (Self, char)> + '_ {
+        self.char_indices().map(move |(i, c)| (self.sub(i..i + c.len_utf8()), c))
+    }
+
+    // the nearest boundary at or before i; anything past the end is the end
+    pub fn floor_char_boundary(&self, i: usize) -> usize {
+        let mut i = i.min(self.len());
+        while !self.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    }
+
+    pub fn ceil_char_boundary(&self, i: usize) -> usize {
+        let mut i = i.min(self.len());
+        while !self.is_char_boundary(i) {
+            i += 1;
+        }
+        i
+    }
+
+    // for offsets from tools that might not agree with us: snaps outward to
+    // char boundaries and clamps to the slice rather than panicking
+    pub fn sub_clamped(&self, index: core::ops::Range<usize>) -> Self {
+        let start = self.floor_char_boundary(index.start);
+        let end = self.ceil_char_boundary(index.end).max(start);
+        self.sub(start..end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use crate::{Indexed, RcSrcStr, Src, SrcStr};
+
+    #[test]
+    fn sub_chars() {
+        let a: SrcStr = "Ecce homo: wie man wird, was man ist — Übermensch".into();
+        let b = a.sub(11..a.len());
+
+        assert_eq!("man wird", &b.sub_chars(4..12)[..]);
+        assert_eq!("— Üb", &b.sub_chars(26..30)[..]);
+        assert_eq!(b.len(), b.char_to_byte(b.char_count()).unwrap());
+        assert_eq!(None, b.char_to_byte(b.char_count() + 1));
+        assert_eq!(28, b.byte_to_char(b.find('Ü').unwrap()));
+        assert_eq!("ä", &RcSrcStr::from_static("aäa").sub_chars(1..2)[..]);
+    }
+
+    #[test]
+    fn indexed_matches_scan() {
+        let text: String = (0..300).map(|i| if i % 7 == 0 { 'ß' } else { 'x' }).collect();
+        let plain: SrcStr = text.clone().into();
+        let indexed: Src<str, _> = Rc::new(Indexed::new(text)).into();
+        let (plain, indexed) = (plain.sub(3..plain.len() - 5), indexed.sub(3..indexed.len() - 5));
+
+        for offset in (0..plain.len()).filter(|&i| plain.is_char_boundary(i)) {
+            assert_eq!(plain.byte_to_char(offset), indexed.byte_to_char(offset));
+        }
+        for char in 0..=plain.char_count() + 1 {
+            assert_eq!(plain.char_to_byte(char), indexed.char_to_byte(char));
+        }
+    }
+
+    #[test]
+    fn char_indices_spanned() {
+        let a: SrcStr = "xäy".into();
+        let b = a.sub(1..a.len());
+
+        let chars: Vec<_> = b.char_indices_spanned().map(|(s, c)| (s.range(), c)).collect();
+        assert_eq!(vec![(Some(1..3), 'ä'), (Some(3..4), 'y')], chars);
+        assert_eq!(None, RcSrcStr::from("").char_indices_spanned().next().map(|(_, c)| c));
+    }
+
+    #[test]
+    fn sub_clamped() {
+        let a: SrcStr = "Morgenröte".into();
+
+        assert_eq!(7, a.floor_char_boundary(8));
+        assert_eq!(9, a.ceil_char_boundary(8));
+        assert_eq!(a.len(), a.floor_char_boundary(100));
+        assert_eq!("rö", &a.sub_clamped(6..8)[..]);
+        assert_eq!("öte", &a.sub_clamped(8..100)[..]);
+        let (start, end) = (9, 3);
+        assert_eq!("", &a.sub_clamped(start..end)[..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_chars_out_of_bounds() {
+        let a: SrcStr = "äbc".into();
+        a.sub_chars(2..4);
+    }
+}
@@ -0,0 +1,184 @@
+use core::cell::RefCell;
+use core::ops::Range;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, SrcStr};
+
+// one logical document made of separately owned chunks, like an editor rope.
+// slices within a chunk share it; slices across chunks are copied out, but
+// still remembered so their logical offset can be found
+#[derive(Clone)]
+pub struct ChunkedSource<P: SharedOwner<str> = Rc<String>> {
+    chunks: Vec<(usize, SrcStr<P>)>, // (logical start, chunk)
+    spliced: RefCell<BTreeMap<(usize, usize), usize>>, // (copy's address, its length) -> logical start
+    len: usize,
+}
+
+// appending only ever adds chunks, so every slice handed out stays valid
+pub type AppendSource<P = Rc<String>> = ChunkedSource<P>;
+
+impl<P: SharedOwner<str>> Default for ChunkedSource<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: SharedOwner<str>> ChunkedSource<P> {
+    pub fn new() -> Self {
+        Self { chunks: Vec::new(), spliced: RefCell::new(BTreeMap::new()), len: 0 }
+    }
+
+    pub fn push(&mut self, chunk: SrcStr<P>) {
+        let len = chunk.len();
+        self.chunks.push((self.len, chunk));
+        self.len += len;
+    }
+
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn append<S: Into<String>>(&mut self, text: S) -> SrcStr<P>
+    where
+        P: From<String>,
+    {
+        let chunk: SrcStr<P> = text.into().into();
+        self.push(chunk.clone());
+        chunk
+    }
+
+    pub fn latest(&self) -> Option<&SrcStr<P>> {
+        self.chunks.last().map(|(_, chunk)| chunk)
+    }
+
+    // range is relative to the latest chunk
+    pub fn slice_of_latest(&self, range: Range<usize>) -> Option<SrcStr<P>> {
+        self.latest()?.get(range)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item = &SrcStr<P>> + '_ {
+        self.chunks.iter().map(|(_, chunk)| chunk)
+    }
+
+    // index of the chunk holding the logical offset (an offset at a chunk's end
+    // belongs to that chunk, so empty slices at the very end still resolve)
+    fn chunk_at(&self, offset: usize) -> Option<usize> {
+        if offset > self.len {
+            return None;
+        }
+        let i = self.chunks.partition_point(|(start, _)| *start <= offset);
+        Some(i.saturating_sub(1))
+    }
+
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn slice(&self, range: Range<usize>) -> Option<SrcStr<P>>
+    where
+        P: From<String>,
+    {
+        if range.start > range.end || range.end > self.len {
+            return None;
+        }
+        let first = self.chunk_at(range.start)?;
+        let (start, chunk) = self.chunks.get(first)?;
+        if range.end <= start + chunk.len() {
+            return chunk.get(range.start - start..range.end - start);
+        }
+
+        let mut text = String::with_capacity(range.len());
+        for (start, chunk) in &self.chunks[first..] {
+            if *start >= range.end {
+                break;
+            }
+            let from = range.start.saturating_sub(*start);
+            let to = (range.end - start).min(chunk.len());
+            text.push_str(&chunk.get(from..to)?);
+        }
+        let spliced: SrcStr<P> = text.into();
+        self.spliced.borrow_mut().insert((spliced.src_ptr() as usize, spliced.len()), range.start);
+        Some(spliced)
+    }
+
+    // where a slice of one of the chunks, or one copied out across them, sits
+    // in the logical document
+    pub fn offset_of(&self, s: &SrcStr<P>) -> Option<usize> {
+        let within = |start: usize, outer: &SrcStr<P>| {
+            let (outer, inner) = (outer.range()?, s.range()?);
+            if outer.start <= inner.start && inner.end <= outer.end {
+                Some(start + inner.start - outer.start)
+            } else {
+                None
+            }
+        };
+        let mut chunks = self.chunks.iter().filter(|(_, chunk)| chunk.src_ptr() == s.src_ptr());
+        chunks.find_map(|(start, chunk)| within(*start, chunk)).or_else(|| {
+            let copy = (s.src_ptr() as usize, s.src()?.elems().len());
+            let start = *self.spliced.borrow().get(&copy)?;
+            Some(start + s.range()?.start)
+        })
+    }
+
+    pub fn range_of(&self, s: &SrcStr<P>) -> Option<Range<usize>> {
+        let start = self.offset_of(s)?;
+        Some(start..start + s.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice() {
+        let mut doc = ChunkedSource::<Rc<String>>::new();
+        doc.push("Out of life's school of war: ".into());
+        doc.push("what does not destroy me, ".into());
+        doc.push("makes me stronger.".into());
+
+        let war = doc.slice(24..27).unwrap();
+        assert_eq!("war", &war[..]);
+        assert_eq!(doc.offset_of(&war), Some(24));
+
+        let across = doc.slice(24..38).unwrap();
+        assert_eq!("war: what does", &across[..]);
+        assert_eq!(doc.offset_of(&across), Some(24));
+        assert_eq!(doc.range_of(&across.sub(5..9)), Some(29..33));
+        assert_eq!(doc.range_of(&war), Some(24..27));
+        assert_eq!(doc.offset_of(&"war".into()), None);
+
+        assert_eq!(doc.slice(doc.len()..doc.len()).map(|s| s.len()), Some(0));
+        assert!(doc.slice(60..80).is_none());
+    }
+
+    #[test]
+    fn append() {
+        let mut repl = AppendSource::<Rc<String>>::new();
+        let first = repl.append("let x = 1\n");
+        let x = first.sub(4..5);
+
+        repl.append("x + 1\n");
+        let plus = repl.slice_of_latest(2..3).unwrap();
+
+        assert_eq!("x", &x[..]);
+        assert_eq!("+", &plus[..]);
+        assert_eq!(repl.offset_of(&plus), Some(12));
+        assert_eq!(repl.latest().map(|s| &s[..]), Some("x + 1\n"));
+    }
+
+    #[test]
+    fn byte_owners() {
+        let mut doc = ChunkedSource::<Rc<[u8]>>::new();
+        doc.push(SrcStr::new(Rc::from(&b"Thus spoke "[..]), 0..11).unwrap());
+        doc.push(SrcStr::new(Rc::from(&b"Zarathustra"[..]), 0..11).unwrap());
+
+        let name = doc.slice_of_latest(0..6).unwrap();
+        assert_eq!(("Zarath", Some(11)), (&name[..], doc.offset_of(&name)));
+    }
+}
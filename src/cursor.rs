@@ -0,0 +1,457 @@
+use core::fmt;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, SrcPattern, SrcStr};
+
+// a lexer's view of the input: everything taken off the front comes back as
+// a slice of the source, so each token keeps its span
+#[derive(Clone, Debug)]
+pub struct SrcCursor<P: SharedOwner<str> = Rc<String>> {
+    rest: SrcStr<P>,
+    tracking: bool,
+    furthest: Option<Furthest<P>>,
+}
+
+// the deepest point an expectation failed at, and everything that was
+// expected there. survives backtracking, so it's what to report once every
+// alternative has failed
+#[derive(Clone, Debug)]
+pub struct Furthest<P: SharedOwner<str> = Rc<String>> {
+    pub at: SrcStr<P>,
+    pub expected: Vec<String>,
+}
+
+impl<P: SharedOwner<str>> Furthest<P> {
+    pub fn to_error(&self) -> ExpectedError<P> {
+        let expected = match &self.expected[..] {
+            [one] => one.clone(),
+            many => alloc::format!("one of {}", many.join(", ")),
+        };
+        ExpectedError { at: self.at.clone(), expected }
+    }
+}
+
+// a saved position to backtrack to, see checkpoint
+#[derive(Clone, Debug)]
+pub struct Mark<P: SharedOwner<str> = Rc<String>> {
+    rest: SrcStr<P>,
+}
+
+// what was expected, and an empty slice where it wasn't found
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExpectedError<P: SharedOwner<str> = Rc<String>> {
+    pub at: SrcStr<P>,
+    pub expected: String,
+}
+
+// by hand, since a derive would want P: Debug
+impl<P: SharedOwner<str>> fmt::Debug for ExpectedError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExpectedError").field("at", &self.at).field("expected", &self.expected).finish()
+    }
+}
+
+impl<P: SharedOwner<str>> fmt::Display for ExpectedError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, col) = self.at.line_col();
+        if let Some(name) = self.at.name() {
+            write!(f, "{}:", name)?;
+        }
+        write!(f, "{}:{}: expected {}", line, col, self.expected)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P: SharedOwner<str>> std::error::Error for ExpectedError<P> {}
+
+// what skip_trivia skips: whitespace, and comments in any of the given
+// syntaxes. nested block comments need their ends to balance
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Trivia<'a> {
+    pub whitespace: bool,
+    pub line_comments: &'a [&'a str],
+    pub block_comments: &'a [(&'a str, &'a str)],
+    pub nested: bool,
+}
+
+impl Trivia<'static> {
+    pub const WHITESPACE: Self = Trivia { whitespace: true, line_comments: &[], block_comments: &[], nested: false };
+    // `//` and `/* */`, not nesting
+    pub const C: Self = Trivia { line_comments: &["//"], block_comments: &[("/*", "*/")], ..Self::WHITESPACE };
+}
+
+impl<P: SharedOwner<str>> SrcCursor<P> {
+    pub fn new(src: SrcStr<P>) -> Self {
+        Self { rest: src, tracking: false, furthest: None }
+    }
+
+    // record the furthest failure from here on, see furthest
+    pub fn with_tracking(mut self) -> Self {
+        self.tracking = true;
+        self
+    }
+
+    pub fn furthest(&self) -> Option<&Furthest<P>> {
+        self.furthest.as_ref()
+    }
+
+    // what hasn't been consumed yet
+    pub fn rest(&self) -> &SrcStr<P> {
+        &self.rest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    pub fn checkpoint(&self) -> Mark<P> {
+        Mark { rest: self.rest.clone() }
+    }
+
+    // back to (or forward to) where the mark was taken. the mark should come
+    // from a cursor over the same source, which debug builds check
+    pub fn reset(&mut self, mark: Mark<P>) {
+        debug_assert!(mark.rest.same_source(&self.rest), "Mark is from a different source");
+        self.rest = mark.rest;
+    }
+
+    pub fn peek_char(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    // n bytes off the front. panics like sub if n is past the end or not on a
+    // char boundary
+    pub fn advance(&mut self, n: usize) -> SrcStr<P> {
+        let (taken, rest) = self.rest.split_at(n);
+        self.rest = rest;
+        taken
+    }
+
+    // advance, but None (consuming nothing) rather than a panic
+    pub fn take(&mut self, n: usize) -> Option<SrcStr<P>> {
+        let (taken, rest) = self.rest.split_at_checked(n)?;
+        self.rest = rest;
+        Some(taken)
+    }
+
+    pub fn take_char(&mut self) -> Option<(char, SrcStr<P>)> {
+        let c = self.peek_char()?;
+        Some((c, self.advance(c.len_utf8())))
+    }
+
+    pub fn eat(&mut self, c: char) -> Option<SrcStr<P>> {
+        if !self.rest.starts_with(c) {
+            return None;
+        }
+        Some(self.advance(c.len_utf8()))
+    }
+
+    // None, without consuming anything, unless the rest starts with s
+    pub fn expect(&mut self, s: &str) -> Option<SrcStr<P>> {
+        if !self.rest.starts_with(s) {
+            return None;
+        }
+        Some(self.advance(s.len()))
+    }
+
+    pub fn expect_str(&mut self, s: &str) -> Result<SrcStr<P>, ExpectedError<P>> {
+        match self.expect(s) {
+            Some(taken) => Ok(taken),
+            None => Err(self.expected(alloc::format!("`{}`", s))),
+        }
+    }
+
+    // an error for something missing at the current position, which the
+    // tracker (if on) takes note of
+    pub fn expected(&mut self, what: impl Into<String>) -> ExpectedError<P> {
+        let err = ExpectedError { at: self.rest.sub(0..0), expected: what.into() };
+        if self.tracking {
+            let at = err.at.place().1.start;
+            match &mut self.furthest {
+                Some(furthest) if furthest.at.place().1.start > at => {},
+                Some(furthest) if furthest.at.place().1.start == at => {
+                    if !furthest.expected.contains(&err.expected) {
+                        furthest.expected.push(err.expected.clone());
+                    }
+                },
+                furthest => *furthest = Some(Furthest { at: err.at.clone(), expected: alloc::vec![err.expected.clone()] }),
+            }
+        }
+        err
+    }
+
+    // everything `trivia` covers, as one slice (empty if there was nothing to
+    // skip). an unterminated block comment runs to the end
+    pub fn skip_trivia(&mut self, trivia: &Trivia<'_>) -> SrcStr<P> {
+        let text = &**self.rest();
+        let mut at = 0;
+        loop {
+            let rest = &text[at..];
+            if trivia.whitespace && rest.starts_with(char::is_whitespace) {
+                at += rest.len() - rest.trim_start().len();
+            } else if trivia.line_comments.iter().any(|start| !start.is_empty() && rest.starts_with(start)) {
+                at += rest.find('\n').map_or(rest.len(), |i| i + 1);
+            } else if let Some(&(open, close)) = trivia.block_comments.iter().find(|(open, _)| !open.is_empty() && rest.starts_with(open)) {
+                at += block_comment_len(rest, open, close, trivia.nested);
+            } else {
+                break;
+            }
+        }
+        self.advance(at)
+    }
+
+    // `_` or XID_Start, then XID_Continue. only ascii ones without the
+    // `unicode-ident` feature
+    pub fn take_identifier(&mut self) -> Option<SrcStr<P>> {
+        let first = self.peek_char()?;
+        let len = if first.is_ascii() {
+            if !(first == '_' || first.is_ascii_alphabetic()) {
+                return None;
+            }
+            // most identifiers are all ascii, so only go char by char past them
+            let bytes = self.rest.as_bytes();
+            let ascii = bytes.iter().position(|&b| !(b.is_ascii_alphanumeric() || b == b'_')).unwrap_or(bytes.len());
+            match bytes.get(ascii) {
+                Some(b) if !b.is_ascii() => xid_continue_len(&self.rest, ascii),
+                _ => ascii,
+            }
+        } else if is_xid_start(first) {
+            xid_continue_len(&self.rest, first.len_utf8())
+        } else {
+            return None;
+        };
+        Some(self.advance(len))
+    }
+
+    // the longest prefix whose chars all match; can be empty
+    pub fn take_while<F: FnMut(char) -> bool>(&mut self, mut pred: F) -> SrcStr<P> {
+        let end = self.rest.char_indices().find(|&(_, c)| !pred(c)).map_or(self.rest.len(), |(i, _)| i);
+        self.advance(end)
+    }
+
+    // everything before the first match, which stays in the rest. takes it
+    // all if there's no match
+    pub fn take_until<Pat: SrcPattern>(&mut self, pattern: Pat) -> SrcStr<P> {
+        let end = pattern.find(&self.rest).unwrap_or(self.rest.len());
+        self.advance(end)
+    }
+}
+
+#[cfg(feature = "unicode-ident")]
+fn is_xid_start(c: char) -> bool {
+    unicode_ident::is_xid_start(c)
+}
+
+#[cfg(not(feature = "unicode-ident"))]
+fn is_xid_start(_: char) -> bool {
+    false
+}
+
+// where the XID_Continue run starting at `from` ends
+#[cfg(feature = "unicode-ident")]
+fn xid_continue_len(text: &str, from: usize) -> usize {
+    let rest = &text[from..];
+    from + rest.char_indices().find(|&(_, c)| !unicode_ident::is_xid_continue(c)).map_or(rest.len(), |(i, _)| i)
+}
+
+#[cfg(not(feature = "unicode-ident"))]
+fn xid_continue_len(_: &str, from: usize) -> usize {
+    from
+}
+
+// text starts with open
+fn block_comment_len(text: &str, open: &str, close: &str, nested: bool) -> usize {
+    let (mut at, mut depth) = (open.len(), 1);
+    while at < text.len() {
+        let rest = &text[at..];
+        if rest.starts_with(close) {
+            at += close.len();
+            depth -= 1;
+            if depth == 0 {
+                return at;
+            }
+        } else if nested && rest.starts_with(open) {
+            at += open.len();
+            depth += 1;
+        } else {
+            at += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    text.len()
+}
+
+impl<P: SharedOwner<str>> From<SrcStr<P>> for SrcCursor<P> {
+    fn from(src: SrcStr<P>) -> Self {
+        Self::new(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use crate::{RcSrcStr, Src};
+
+    #[test]
+    fn cursor() {
+        let a: SrcStr = "let ä = 1;".into();
+        let mut cursor = SrcCursor::new(a.clone());
+
+        assert_eq!(Some('l'), cursor.peek_char());
+        assert_eq!(Some(0..3), cursor.expect("let").and_then(|s| s.range()));
+        assert!(cursor.expect("let").is_none());
+        assert!(cursor.eat(' ').is_some());
+        assert_eq!(Some('ä'), cursor.peek_char());
+        assert_eq!(Some(4..6), cursor.advance(2).range());
+        assert_eq!(" = 1;", &cursor.rest()[..]);
+        assert!(cursor.eat('=').is_none());
+
+        cursor.advance(cursor.rest().len());
+        assert!(cursor.is_empty());
+        assert_eq!(None, cursor.peek_char());
+    }
+
+    #[test]
+    fn expect_str() {
+        let a: SrcStr = "let x\nin y".into();
+        let mut cursor = SrcCursor::new(a.clone());
+
+        assert_eq!(Some(0..3), cursor.expect_str("let").unwrap().range());
+        let err = cursor.expect_str("=").unwrap_err();
+        assert_eq!(Some(3..3), err.at.range());
+        assert_eq!("1:4: expected `=`", err.to_string());
+
+        cursor.advance(3);
+        assert!(cursor.expect_str("in").is_ok());
+        let named = Src::named("main.ml", "let");
+        assert_eq!("main.ml:1:1: expected `in`", SrcCursor::new(named).expect_str("in").unwrap_err().to_string());
+    }
+
+    #[test]
+    fn checkpoint() {
+        let a: SrcStr = "a.b.c".into();
+        let mut cursor = SrcCursor::new(a);
+
+        cursor.advance(1);
+        let mark = cursor.checkpoint();
+        cursor.advance(3);
+        let later = cursor.checkpoint();
+        cursor.reset(mark);
+        assert_eq!(Some(1..5), cursor.rest().range());
+        cursor.reset(later);
+        assert_eq!("c", &cursor.rest()[..]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn reset_other_source() {
+        let mut cursor = SrcCursor::new(RcSrcStr::from("a"));
+        cursor.reset(SrcCursor::new(RcSrcStr::from("a")).checkpoint());
+    }
+
+    #[test]
+    fn furthest() {
+        let a: SrcStr = "let x = ;".into();
+        let mut cursor = SrcCursor::new(a).with_tracking();
+
+        assert!(cursor.expect_str("fn").is_err());
+        let start = cursor.checkpoint();
+        cursor.advance(8);
+        assert!(cursor.expect_str("1").is_err());
+        assert!(cursor.expect_str("(").is_err());
+        assert!(cursor.expect_str("1").is_err());
+        cursor.reset(start);
+        assert!(cursor.expect_str("const").is_err());
+
+        let furthest = cursor.furthest().unwrap();
+        assert_eq!(Some(8..8), furthest.at.range());
+        assert_eq!("1:9: expected one of `1`, `(`", furthest.to_error().to_string());
+        assert!(SrcCursor::new(RcSrcStr::from("")).furthest().is_none());
+    }
+
+    #[test]
+    fn skip_trivia() {
+        let a: SrcStr = "  // one\n /* two */\tx /* a /* b */ c */y".into();
+        let mut cursor = SrcCursor::new(a);
+
+        assert_eq!(Some(0..20), cursor.skip_trivia(&Trivia::C).range());
+        assert!(cursor.eat('x').is_some());
+
+        let start = cursor.checkpoint();
+        cursor.skip_trivia(&Trivia::C);
+        assert_eq!("c */y", &cursor.rest()[..]);
+        cursor.reset(start);
+        cursor.skip_trivia(&Trivia { nested: true, ..Trivia::C });
+        assert_eq!("y", &cursor.rest()[..]);
+
+        assert_eq!("", &cursor.skip_trivia(&Trivia::WHITESPACE)[..]);
+        let mut hash = SrcCursor::new(RcSrcStr::from("# note\n(* open"));
+        let ml = Trivia { line_comments: &["#"], block_comments: &[("(*", "*)")], ..Trivia::WHITESPACE };
+        hash.skip_trivia(&ml);
+        assert!(hash.is_empty());
+    }
+
+    #[test]
+    fn take_identifier() {
+        let a: SrcStr = "_tmp2 + 3x".into();
+        let mut cursor = SrcCursor::new(a);
+
+        assert_eq!(Some(0..5), cursor.take_identifier().and_then(|s| s.range()));
+        assert!(cursor.take_identifier().is_none());
+        cursor.advance(3);
+        assert!(cursor.take_identifier().is_none());
+        assert_eq!(Some('3'), cursor.peek_char());
+    }
+
+    #[test]
+    fn take_identifier_unicode() {
+        let mut cursor = SrcCursor::new(RcSrcStr::from("größe·2 = 名前"));
+        let ident = cursor.take_identifier();
+        let mut cjk = SrcCursor::new(RcSrcStr::from("名前"));
+
+        if cfg!(feature = "unicode-ident") {
+            assert_eq!("größe·2", &ident.unwrap()[..]);
+            assert_eq!("名前", &cjk.take_identifier().unwrap()[..]);
+        } else {
+            assert_eq!("gr", &ident.unwrap()[..]);
+            assert!(cjk.take_identifier().is_none());
+        }
+    }
+
+    #[test]
+    fn take() {
+        let a: SrcStr = "äb".into();
+        let mut cursor = SrcCursor::new(a);
+
+        assert!(cursor.take(1).is_none());
+        assert!(cursor.take(4).is_none());
+        let (c, s) = cursor.take_char().unwrap();
+        assert_eq!(('ä', Some(0..2)), (c, s.range()));
+        assert_eq!(Some(2..3), cursor.take(1).and_then(|s| s.range()));
+        assert!(cursor.take_char().is_none());
+        assert_eq!(Some(3..3), cursor.take(0).and_then(|s| s.range()));
+    }
+
+    #[test]
+    fn take_while() {
+        let a: SrcStr = "count2 += 10 // über".into();
+        let mut cursor = SrcCursor::new(a);
+
+        assert_eq!(Some(0..6), cursor.take_while(|c| c.is_alphanumeric()).range());
+        assert_eq!("", &cursor.take_while(char::is_alphanumeric)[..]);
+        assert_eq!(" += 10 ", &cursor.take_until("//")[..]);
+        assert_eq!("// ", &cursor.take_until(char::is_alphabetic)[..]);
+        assert_eq!("über", &cursor.take_until('\n')[..]);
+        assert!(cursor.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn advance_inside_char() {
+        let a: SrcStr = "ä".into();
+        SrcCursor::new(a).advance(1);
+    }
+}
@@ -0,0 +1,91 @@
+use core::ops::Range;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, SourceBacking, Src, SrcStr};
+
+pub type DerivedSrcStr<P = Rc<String>> = Src<str, Rc<Derived<P>>>;
+
+// text computed from another source (an unescaped literal, a macro expansion),
+// with pieces of it mapped back to where they came from
+pub struct Derived<P: SharedOwner<str> = Rc<String>> {
+    text: String,
+    origin: SrcStr<P>,
+    mapping: Vec<(Range<usize>, Range<usize>)>, // (derived range, origin range)
+}
+
+impl<P: SharedOwner<str>> Derived<P> {
+    pub fn origin(&self) -> &SrcStr<P> {
+        &self.origin
+    }
+}
+
+unsafe impl<P: SharedOwner<str>> SourceBacking for Derived<P> {
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.origin.name()
+    }
+}
+
+impl<P: SharedOwner<str>> Src<str, P> {
+    // mapping ranges are relative to the new text and to `self`
+    pub fn derive(&self, text: impl Into<String>, mapping: Vec<(Range<usize>, Range<usize>)>) -> DerivedSrcStr<P> {
+        Rc::new(Derived { text: text.into(), origin: self.clone(), mapping }).into()
+    }
+}
+
+impl<P: SharedOwner<str>> Src<str, Rc<Derived<P>>> {
+    // the origin span covering every mapped piece this slice touches. pieces of
+    // equal length are taken to be copied, so they map byte for byte
+    pub fn origin_span(&self) -> Option<SrcStr<P>> {
+        let derived = self.src()?;
+        let range = self.range()?;
+        let mut covered: Option<Range<usize>> = None;
+        for (to, from) in &derived.mapping {
+            let touches = if range.is_empty() {
+                to.start <= range.start && range.start <= to.end
+            } else {
+                to.start < range.end && range.start < to.end
+            };
+            if !touches {
+                continue;
+            }
+            let piece = if to.len() == from.len() {
+                let start = range.start.max(to.start) - to.start;
+                let end = range.end.min(to.end) - to.start;
+                from.start + start..from.start + end
+            } else {
+                from.clone()
+            };
+            covered = Some(match covered {
+                Some(c) => c.start.min(piece.start)..c.end.max(piece.end),
+                None => piece,
+            });
+        }
+        let covered = covered?;
+        derived.origin.get(covered.clone())?;
+        Some(derived.origin.sub(covered))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn origin_span() {
+        let code: SrcStr = r#"print("a\tb");"#.into();
+        let literal = code.sub(7..11);
+        let unescaped = literal.derive("a\tb", vec![(0..1, 0..1), (1..2, 1..3), (2..3, 3..4)]);
+
+        assert_eq!("\\t", &unescaped.sub(1..2).origin_span().unwrap()[..]);
+        assert_eq!("a\\t", &unescaped.sub(0..2).origin_span().unwrap()[..]);
+        assert_eq!(unescaped.sub(2..3).origin_span().unwrap().range(), Some(10..11));
+        assert_eq!(unescaped.src().unwrap().origin().range(), Some(7..11));
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use core::fmt;
+use core::ops::{Range, RangeBounds};
+
+use crate::{Repr, SharedOwner, Slice, Src};
+
+impl<T, P> Src<T, P>
+where
+    T: ?Sized + Slice + ToOwned,
+    P: SharedOwner<T> + From<<T as ToOwned>::Owned>,
+{
+    // copies just this slice into its own owner, so it stops pinning the source.
+    // the copy is its own source, so it comes back with the old source's name
+    // and its range there
+    pub fn detach(&self) -> Detached<T, P> {
+        match self.repr {
+            Repr::Range { .. } => Detached {
+                src: Self::whole(P::from((**self).to_owned())),
+                origin: Some((self.name().map(String::from), self.range().unwrap())),
+            },
+            Repr::Static(_) => Detached { src: self.clone(), origin: None },
+        }
+    }
+
+    // the copy alone: its name, meta and indexes are gone, and its range
+    // starts again at 0
+    pub fn detach_in_place(&mut self) {
+        *self = self.detach().into_src();
+    }
+}
+
+// a slice that might have been copied out of its source, still knowing that
+// source's name and where in it the slice sat. meta doesn't come along, since
+// there's no way to clone it
+pub struct Detached<T: ?Sized + Slice, P: SharedOwner<T>> {
+    src: Src<T, P>,
+    origin: Option<(Option<String>, Range<usize>)>, // only for copies
+}
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Detached<T, P> {
+    // the slice itself: the copy, or the original if it wasn't copied
+    pub fn src(&self) -> &Src<T, P> {
+        &self.src
+    }
+
+    pub fn into_src(self) -> Src<T, P> {
+        self.src
+    }
+
+    pub fn is_copy(&self) -> bool {
+        self.origin.is_some()
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        match &self.origin {
+            Some((name, _)) => name.as_deref(),
+            None => self.src.name(),
+        }
+    }
+
+    // in the source the slice was taken from
+    pub fn range(&self) -> Option<Range<usize>> {
+        match &self.origin {
+            Some((_, range)) => Some(range.clone()),
+            None => self.src.range(),
+        }
+    }
+}
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Clone for Detached<T, P> {
+    fn clone(&self) -> Self {
+        Self { src: self.src.clone(), origin: self.origin.clone() }
+    }
+}
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> fmt::Debug for Detached<T, P>
+where
+    Src<T, P>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Detached").field("src", &self.src).field("name", &self.name()).field("range", &self.range()).finish()
+    }
+}
+
+// detaches slices of at most `max_slice` elements from sources of at least
+// `min_source` elements; anything else is kept as is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DetachPolicy {
+    pub max_slice: usize,
+    pub min_source: usize,
+}
+
+impl DetachPolicy {
+    pub fn new(max_slice: usize, min_source: usize) -> Self {
+        Self { max_slice, min_source }
+    }
+
+    pub fn should_detach<T: ?Sized + Slice, P: SharedOwner<T>>(&self, s: &Src<T, P>) -> bool {
+        match (&s.rc, s.repr) {
+            (Some(rc), Repr::Range { len, .. }) => len <= self.max_slice && rc.elems().len() >= self.min_source,
+            _ => false,
+        }
+    }
+}
+
+impl<T, P> Src<T, P>
+where
+    T: ?Sized + Slice + ToOwned,
+    P: SharedOwner<T> + From<<T as ToOwned>::Owned>,
+{
+    pub fn detach_with(&self, policy: &DetachPolicy) -> Detached<T, P> {
+        if policy.should_detach(self) {
+            self.detach()
+        } else {
+            Detached { src: self.clone(), origin: None }
+        }
+    }
+}
+
+// a source whose slices go through a policy on the way out, so tables built
+// from it only pin the source when the policy allows
+pub struct Detaching<T: ?Sized + Slice, P: SharedOwner<T>> {
+    src: Src<T, P>,
+    policy: DetachPolicy,
+}
+
+impl<T, P> Detaching<T, P>
+where
+    T: ?Sized + Slice + ToOwned,
+    P: SharedOwner<T> + From<<T as ToOwned>::Owned>,
+{
+    pub fn new(src: Src<T, P>, policy: DetachPolicy) -> Self {
+        Self { src, policy }
+    }
+
+    pub fn src(&self) -> &Src<T, P> {
+        &self.src
+    }
+
+    pub fn policy(&self) -> &DetachPolicy {
+        &self.policy
+    }
+
+    pub fn into_inner(self) -> Src<T, P> {
+        self.src
+    }
+
+    pub fn sub(&self, index: impl RangeBounds<usize>) -> Detached<T, P> {
+        self.src.sub(index).detach_with(&self.policy)
+    }
+
+    pub fn get(&self, index: Range<usize>) -> Option<Detached<T, P>> {
+        Some(self.src.get(index)?.detach_with(&self.policy))
+    }
+
+    pub fn slice_ref(&self, subset: &T) -> Option<Detached<T, P>> {
+        Some(self.src.slice_ref(subset)?.detach_with(&self.policy))
+    }
+
+    // for slices taken some other way (a lexer, a regex) before they're stored
+    pub fn keep(&self, s: &Src<T, P>) -> Detached<T, P> {
+        s.detach_with(&self.policy)
+    }
+}
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Clone for Detaching<T, P> {
+    fn clone(&self) -> Self {
+        Self { src: self.src.clone(), policy: self.policy }
+    }
+}
+
+impl<T: ?Sized + Slice + fmt::Debug, P: SharedOwner<T>> fmt::Debug for Detaching<T, P>
+where
+    Src<T, P>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Detaching").field("src", &self.src).field("policy", &self.policy).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use super::*;
+    use crate::{RcSrcStr, SrcStr};
+
+    #[test]
+    fn detach() {
+        let a: SrcStr = "The snake which cannot cast its skin has to die.".into();
+        let mut b = a.sub(4..9);
+        let c = b.detach();
+
+        assert_eq!("snake", &c.src()[..]);
+        assert_eq!((c.src().range(), c.range()), (Some(0..5), Some(4..9)));
+        assert_eq!(Rc::strong_count(a.src().unwrap()), 2);
+
+        b.detach_in_place();
+        assert_eq!(Rc::strong_count(a.src().unwrap()), 1);
+        assert_eq!(String::from(b), "snake");
+        assert!(!RcSrcStr::from_static("skin").detach().is_copy());
+    }
+
+    // a named owner that can also be built from bare text, so it can be detached
+    #[derive(Clone)]
+    struct Labeled(Rc<(String, Option<&'static str>)>);
+
+    unsafe impl SharedOwner<str> for Labeled {
+        fn elems(&self) -> &[u8] {
+            self.0 .0.as_bytes()
+        }
+
+        fn whole(&self) -> Option<&str> {
+            Some(&self.0 .0)
+        }
+
+        fn name(&self) -> Option<&str> {
+            self.0 .1
+        }
+    }
+
+    impl From<String> for Labeled {
+        fn from(text: String) -> Self {
+            Labeled(Rc::new((text, None)))
+        }
+    }
+
+    #[test]
+    fn detach_keeps_name() {
+        let a = Src::<str, _>::new(Labeled(Rc::new(("Whatever is done from love".into(), Some("dawn.txt")))), 0..26).unwrap();
+        let love = a.sub(22..26).detach();
+
+        assert!(love.is_copy());
+        assert_eq!((None, Some("dawn.txt")), (love.src().name(), love.name()));
+        assert_eq!(Some(22..26), love.range());
+    }
+
+    #[test]
+    fn policy() {
+        let a: SrcStr = "Convictions are more dangerous foes of truth than lies.".into();
+        let policy = DetachPolicy::new(8, 32);
+
+        let long = a.sub(16..30).detach_with(&policy);
+        assert_eq!((long.is_copy(), long.src().range()), (false, Some(16..30)));
+        let foes = a.sub(31..35).detach_with(&policy);
+        assert_eq!((foes.src().range(), foes.range()), (Some(0..4), Some(31..35)));
+        assert!(!DetachPolicy::new(8, 1000).should_detach(&a.sub(31..35)));
+    }
+
+    #[test]
+    fn detaching() {
+        let a: SrcStr = "Convictions are more dangerous foes of truth than lies.".into();
+        let table = Detaching::new(a.clone(), DetachPolicy::new(8, 32));
+
+        let tokens = [table.sub(16..30), table.get(31..35).unwrap(), table.keep(&a.sub(50..54))];
+        assert_eq!(tokens.iter().map(Detached::range).collect::<alloc::vec::Vec<_>>(), [Some(16..30), Some(31..35), Some(50..54)]);
+        assert_eq!((&tokens[1].src()[..], tokens[1].src().range()), ("foes", Some(0..4)));
+        assert_eq!(&tokens[2].src()[..], "lies");
+        assert_eq!(Rc::strong_count(a.src().unwrap()), 3);
+    }
+}
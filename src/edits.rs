@@ -0,0 +1,214 @@
+use core::fmt;
+use core::ops::Range;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{RangeError, SharedOwner, Src, SrcStr};
+
+// replace `range` (relative to the text being edited) with `text`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, text: impl Into<String>) -> Self {
+        Self { range, text: text.into() }
+    }
+
+    // the single edit turning `old` into `new`, covering everything between
+    // their common prefix and suffix
+    pub fn between(old: &str, new: &str) -> Self {
+        let mut prefix = old.bytes().zip(new.bytes()).take_while(|(a, b)| a == b).count();
+        while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        let max = old.len().min(new.len()) - prefix;
+        let mut suffix = old.bytes().rev().zip(new.bytes().rev()).take(max).take_while(|(a, b)| a == b).count();
+        while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+            suffix -= 1;
+        }
+        Self::new(prefix..old.len() - suffix, &new[prefix..new.len() - suffix])
+    }
+}
+
+// why a batch of edits couldn't be applied
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EditError {
+    // an edit's range can't be sliced out of the text being edited
+    Range { range: Range<usize>, error: RangeError },
+    // two edits replace some of the same text
+    Overlapping { first: Range<usize>, second: Range<usize> },
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::Range { range, error } => write!(f, "edit of {:?}: {}", range, error),
+            EditError::Overlapping { first, second } => write!(f, "edits of {:?} and {:?} overlap", first, second),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EditError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EditError::Range { error, .. } => Some(error),
+            EditError::Overlapping { .. } => None,
+        }
+    }
+}
+
+// carries spans of the old text over to the text produced by `apply_edits`
+pub struct SpanRemapper<P: SharedOwner<str> = Rc<String>> {
+    old: SrcStr<P>,
+    new: SrcStr<P>,
+    edits: Vec<(Range<usize>, usize)>, // (replaced range, length of the new text), sorted
+}
+
+impl<P: SharedOwner<str>> SpanRemapper<P> {
+    pub fn old(&self) -> &SrcStr<P> {
+        &self.old
+    }
+
+    pub fn new_src(&self) -> &SrcStr<P> {
+        &self.new
+    }
+
+    // None if the offset was inside replaced text
+    pub fn remap_offset(&self, offset: usize) -> Option<usize> {
+        let (mut added, mut removed) = (0, 0);
+        for (range, len) in &self.edits {
+            if offset <= range.start {
+                break;
+            }
+            if offset < range.end {
+                return None;
+            }
+            added += len;
+            removed += range.len();
+        }
+        Some(offset + added - removed)
+    }
+
+    // None if the span overlapped an edit (so it's gone), or isn't from the old
+    // text. text inserted strictly inside the span becomes part of it
+    pub fn remap(&self, span: &SrcStr<P>) -> Option<SrcStr<P>> {
+        let local = local_range(&self.old, span)?;
+        let (mut added, mut removed, mut grown) = (0, 0, 0);
+        for (range, len) in &self.edits {
+            if local.end <= range.start {
+                break;
+            }
+            if range.is_empty() && local.start < range.start {
+                grown += len;
+                continue;
+            }
+            if local.start < range.end {
+                return None;
+            }
+            added += len;
+            removed += range.len();
+        }
+        let start = local.start + added - removed;
+        Some(self.new.sub(start..start + local.len() + grown))
+    }
+}
+
+// where `span` sits inside `outer`, if it comes from the same owner
+fn local_range<P: SharedOwner<str>>(outer: &SrcStr<P>, span: &SrcStr<P>) -> Option<Range<usize>> {
+    let (outer_range, range) = (outer.range()?, span.range()?);
+    if outer.src_ptr() != span.src_ptr() || range.start < outer_range.start || range.end > outer_range.end {
+        return None;
+    }
+    Some(range.start - outer_range.start..range.end - outer_range.start)
+}
+
+impl<P: SharedOwner<str> + From<String>> Src<str, P> {
+    // edits may come in any order, but mustn't overlap. they're usually from
+    // other tools, so bad ones are an error rather than a panic
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn apply_edits(&self, edits: &[TextEdit]) -> Result<(Self, SpanRemapper<P>), EditError> {
+        let mut sorted: Vec<&TextEdit> = edits.iter().collect();
+        sorted.sort_by_key(|edit| (edit.range.start, edit.range.end));
+
+        let mut text = String::with_capacity(self.len());
+        let mut at = 0;
+        for (i, edit) in sorted.iter().enumerate() {
+            let range = edit.range.clone();
+            if let Err(error) = self.try_sub(range.clone()) {
+                return Err(EditError::Range { range, error });
+            }
+            if range.start < at {
+                return Err(EditError::Overlapping { first: sorted[i - 1].range.clone(), second: range });
+            }
+            text.push_str(&self[at..range.start]);
+            text.push_str(&edit.text);
+            at = range.end;
+        }
+        text.push_str(&self[at..]);
+
+        let new: Self = text.into();
+        let edits = sorted.iter().map(|edit| (edit.range.clone(), edit.text.len())).collect();
+        Ok((new.clone(), SpanRemapper { old: self.clone(), new, edits }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn remap() {
+        let a: SrcStr = "let x = 1; let y = x;".into();
+        let y = a.sub(15..16);
+        let x = a.sub(4..5);
+
+        let (b, remap) = a.apply_edits(&[TextEdit::new(19..20, "z"), TextEdit::new(0..3, "const")]).unwrap();
+        assert_eq!("const x = 1; let y = z;", &b[..]);
+
+        assert_eq!("y", &remap.remap(&y).unwrap()[..]);
+        assert_eq!(remap.remap(&y).unwrap().range(), Some(17..18));
+        assert!(remap.remap(&x).is_some());
+        assert!(remap.remap(&a.sub(0..5)).is_none());
+        assert!(remap.remap(&"elsewhere".into()).is_none());
+
+        assert_eq!(Some(0), remap.remap_offset(0));
+        assert_eq!(None, remap.remap_offset(1));
+        assert_eq!(Some(23), remap.remap_offset(21));
+    }
+
+    #[test]
+    fn between() {
+        assert_eq!(TextEdit::new(4..5, "yz"), TextEdit::between("let x = 1;", "let yz = 1;"));
+        assert_eq!(TextEdit::new(3..3, "a"), TextEdit::between("aaa", "aaaa"));
+        assert_eq!(TextEdit::new(0..2, "ö"), TextEdit::between("ä", "ö"));
+    }
+
+    #[test]
+    fn insertion_inside() {
+        let a: SrcStr = "f(a, c)".into();
+        let args = a.sub(2..6);
+
+        let (b, remap) = a.apply_edits(&[TextEdit::new(5..5, "b, "), TextEdit::new(2..2, "_")]).unwrap();
+        assert_eq!("f(_a, b, c)", &b[..]);
+        assert_eq!("a, b, c", &remap.remap(&args).unwrap()[..]);
+        assert_eq!("f", &remap.remap(&a.sub(0..1)).unwrap()[..]);
+        assert_eq!("(_a", &remap.remap(&a.sub(1..3)).unwrap()[..]);
+    }
+
+    #[test]
+    fn bad_edits() {
+        let a: SrcStr = "overlap".into();
+
+        let err = a.apply_edits(&[TextEdit::new(2..6, ""), TextEdit::new(0..4, "")]).err();
+        assert_eq!(Some(EditError::Overlapping { first: 0..4, second: 2..6 }), err);
+        let err = a.apply_edits(&[TextEdit::new(5..9, "")]).err().unwrap();
+        assert_eq!(EditError::Range { range: 5..9, error: RangeError::OutOfBounds { range: 5..9, len: 7 } }, err);
+        assert_eq!("edit of 5..9: range 5..9 is out of bounds for length 7", err.to_string());
+    }
+}
@@ -0,0 +1,78 @@
+use core::fmt;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+
+use crate::{SourceBacking, Src};
+
+pub type EmbeddedSrcStr = Src<str, Rc<Embedded>>;
+
+// where in the rust code a snippet was written
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Site {
+    pub file: &'static str,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for Site {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+// a snippet embedded in rust source, named after (and carrying as meta) its site
+pub struct Embedded {
+    text: &'static str,
+    name: String,
+    site: Site,
+}
+
+unsafe impl SourceBacking for Embedded {
+    fn as_str(&self) -> &str {
+        self.text
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn meta(&self) -> Option<&dyn core::any::Any> {
+        Some(&self.site)
+    }
+}
+
+impl Src<str, Rc<Embedded>> {
+    pub fn embedded(text: &'static str, site: Site) -> Self {
+        Rc::new(Embedded { text, name: format!("{}", site), site }).into()
+    }
+
+    pub fn site(&self) -> Option<&Site> {
+        self.meta()
+    }
+}
+
+// a source for a dsl snippet that remembers the file, line and column of the
+// literal, so diagnostics can point at the rust code that embedded it
+#[macro_export]
+macro_rules! srcstr {
+    ($text:expr) => {
+        $crate::EmbeddedSrcStr::embedded(
+            $text,
+            $crate::Site { file: file!(), line: line!(), column: column!() },
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn srcstr() {
+        let (a, line) = (srcstr!("select * from idols"), line!());
+        let site = a.sub(7..8).site().copied().unwrap();
+
+        assert_eq!(file!(), site.file);
+        assert_eq!(line, site.line);
+        assert_eq!(a.name(), Some(&*std::format!("{}:{}:{}", file!(), line, site.column)));
+    }
+}
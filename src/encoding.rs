@@ -0,0 +1,52 @@
+use alloc::string::String;
+use encoding_rs::Encoding;
+
+use crate::{SharedOwner, Src, WithMeta};
+
+// how a source was stored before being decoded to utf-8, kept as its meta
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceEncoding {
+    pub encoding: &'static Encoding,
+    pub had_bom: bool,
+    pub had_errors: bool, // malformed input was replaced with U+FFFD
+}
+
+impl<P: SharedOwner<str> + From<WithMeta<SourceEncoding>>> Src<str, P> {
+    // a BOM wins over `fallback`, e.g. encoding_rs::WINDOWS_1252 for latin-1
+    pub fn decode(bytes: &[u8], fallback: &'static Encoding) -> Self {
+        let had_bom = Encoding::for_bom(bytes).is_some();
+        let (text, encoding, had_errors) = fallback.decode(bytes);
+        let meta = SourceEncoding { encoding, had_bom, had_errors };
+        Self::whole(P::from(WithMeta::new(meta, String::from(text))))
+    }
+
+    #[cfg(feature = "std")]
+    pub fn load_decoded(path: &std::path::Path, fallback: &'static Encoding) -> std::io::Result<Self> {
+        Ok(Self::decode(&std::fs::read(path)?, fallback))
+    }
+
+    pub fn encoding(&self) -> Option<&SourceEncoding> {
+        self.meta()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use encoding_rs::{UTF_16LE, UTF_8, WINDOWS_1252};
+
+    type Decoded = Src<str, Rc<WithMeta<SourceEncoding>>>;
+
+    #[test]
+    fn decode() {
+        let a = Decoded::decode(b"\xff\xfeu\x00b\x00e\x00r\x00", UTF_8);
+        assert_eq!("uber", &a[..]);
+        assert_eq!(Some(&SourceEncoding { encoding: UTF_16LE, had_bom: true, had_errors: false }), a.encoding());
+
+        let b = Decoded::decode(b"\xdcbermensch", WINDOWS_1252);
+        assert_eq!("Übermensch", &b[..]);
+        assert_eq!(WINDOWS_1252, b.sub(2..6).encoding().unwrap().encoding);
+        assert!(!b.encoding().unwrap().had_bom);
+    }
+}
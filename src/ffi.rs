@@ -0,0 +1,81 @@
+use alloc::boxed::Box;
+use core::ffi::c_void;
+
+use crate::SyncSrcStr;
+
+// what C sees; `owner` keeps the source alive until srcstr_drop
+#[repr(C)]
+pub struct SrcStrView {
+    pub owner: *mut c_void,
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl SrcStrView {
+    pub fn new(s: SyncSrcStr) -> Self {
+        let (ptr, len) = (s.as_ptr(), s.len());
+        let owner = Box::into_raw(Box::new(s)) as *mut c_void;
+        Self { owner, ptr, len }
+    }
+
+    /// # Safety
+    ///
+    /// The view must have come from `SrcStrView::new` (or `srcstr_clone`) and
+    /// not been dropped yet.
+    pub unsafe fn into_srcstr(self) -> SyncSrcStr {
+        *Box::from_raw(self.owner as *mut SyncSrcStr)
+    }
+
+    unsafe fn srcstr(&self) -> &SyncSrcStr {
+        &*(self.owner as *const SyncSrcStr)
+    }
+}
+
+/// # Safety
+///
+/// `view` must point to a live view.
+#[no_mangle]
+pub unsafe extern "C" fn srcstr_clone(view: *const SrcStrView) -> SrcStrView {
+    SrcStrView::new((*view).srcstr().clone())
+}
+
+/// # Safety
+///
+/// `view` must be a live view, and isn't usable afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn srcstr_drop(view: SrcStrView) {
+    drop(view.into_srcstr());
+}
+
+/// # Safety
+///
+/// `view` must point to a live view, and `len` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn srcstr_data(view: *const SrcStrView, len: *mut usize) -> *const u8 {
+    let s = (*view).srcstr();
+    if !len.is_null() {
+        *len = s.len();
+    }
+    s.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let a: SyncSrcStr = "All things are subject to interpretation.".into();
+        let view = SrcStrView::new(a.sub(15..22));
+
+        unsafe {
+            let copy = srcstr_clone(&view);
+            srcstr_drop(view);
+
+            let mut len = 0;
+            let ptr = srcstr_data(&copy, &mut len);
+            assert_eq!(core::slice::from_raw_parts(ptr, len), b"subject");
+            assert_eq!(copy.into_srcstr(), a.sub(15..22));
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use core::ops::Range;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, SrcStr};
+
+// a source written with its spans marked underneath:
+//
+//     let x = 1;
+//         ^ name
+//
+// a line of only spaces and carets, with an optional label, marks the source
+// line above it (counting chars) and is left out of the source
+pub struct Fixture<P: SharedOwner<str> = Rc<String>> {
+    src: SrcStr<P>,
+    spans: Vec<(String, SrcStr<P>)>,
+}
+
+fn marker(line: &str) -> Option<(usize, usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    let rest = &line[indent..];
+    let carets = rest.len() - rest.trim_start_matches('^').len();
+    let label = rest[carets..].trim();
+    if carets == 0 || label.contains('^') {
+        return None;
+    }
+    Some((indent, carets, label))
+}
+
+impl<P: SharedOwner<str> + From<String>> Fixture<P> {
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn parse(text: &str) -> Self {
+        let mut src = String::with_capacity(text.len());
+        let mut marks: Vec<(String, Range<usize>)> = Vec::new();
+        let mut above: Option<Range<usize>> = None;
+
+        for line in text.split_inclusive('\n') {
+            let body = line.trim_end_matches(&['\n', '\r'][..]);
+            if let (Some(above), Some((col, len, label))) = (&above, marker(body)) {
+                let chars = &src[above.clone()];
+                let mut starts = chars.char_indices().map(|(i, _)| i).chain(Some(chars.len()));
+                let start = starts.nth(col).expect("marker starts past the end of the line");
+                let end = starts.nth(len - 1).expect("marker runs past the end of the line");
+                marks.push((label.to_string(), above.start + start..above.start + end));
+                continue;
+            }
+            let start = src.len();
+            src.push_str(line);
+            above = Some(start..start + body.len());
+        }
+
+        let src: SrcStr<P> = src.into();
+        let spans = marks.into_iter().map(|(label, range)| (label, src.sub(range))).collect();
+        Self { src, spans }
+    }
+}
+
+impl<P: SharedOwner<str>> Fixture<P> {
+    pub fn src(&self) -> &SrcStr<P> {
+        &self.src
+    }
+
+    // the first span with this label
+    pub fn span(&self, label: &str) -> Option<&SrcStr<P>> {
+        self.spans.iter().find(|(l, _)| l == label).map(|(_, span)| span)
+    }
+
+    pub fn spans(&self) -> impl Iterator<Item = (&str, &SrcStr<P>)> + '_ {
+        self.spans.iter().map(|(label, span)| (&**label, span))
+    }
+}
+
+#[macro_export]
+macro_rules! src_fixture {
+    ($text:expr) => {
+        <$crate::Fixture>::parse($text)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn src_fixture() {
+        let fixture = src_fixture!(
+            "let über = 1;\n    ^^^^ name\n         ^ eq\nlet y = über;\n        ^^^^ use\n"
+        );
+
+        assert_eq!("let über = 1;\nlet y = über;\n", &fixture.src()[..]);
+        assert_eq!("über", &fixture.span("name").unwrap()[..]);
+        assert_eq!(Some(4..9), fixture.span("name").unwrap().range());
+        assert_eq!("=", &fixture.span("eq").unwrap()[..]);
+        assert_eq!(Some(23..28), fixture.span("use").unwrap().range());
+        assert_eq!(3, fixture.spans().count());
+    }
+}
@@ -0,0 +1,67 @@
+use core::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{bad_index, SharedOwner, Src};
+
+// extended grapheme clusters, so a flag or an e + combining accent is never
+// split in half
+impl<P: SharedOwner<str>> Src<str, P> {
+    pub fn graphemes(&self) -> impl Iterator<Item = Self> + '_ {
+        UnicodeSegmentation::graphemes(&**self, true).map(move |g| self.subslice(g))
+    }
+
+    pub fn grapheme_count(&self) -> usize {
+        UnicodeSegmentation::graphemes(&**self, true).count()
+    }
+
+    // like sub, but counting graphemes rather than bytes
+    pub fn sub_graphemes(&self, index: Range<usize>) -> Self {
+        if index.start > index.end {
+            bad_index(index);
+        }
+        let mut bounds = self.grapheme_indices(true).map(|(i, _)| i).chain(Some(self.len()));
+        let start = bounds.nth(index.start);
+        let end = match index.end - index.start {
+            0 => start,
+            n => bounds.nth(n - 1),
+        };
+        match (start, end) {
+            (Some(start), Some(end)) => self.sub(start..end),
+            _ => bad_index(index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use crate::SrcStr;
+
+    #[test]
+    fn graphemes() {
+        let a: SrcStr = "e\u{301}🇩🇪x".into();
+        let graphemes: Vec<_> = a.graphemes().collect();
+
+        assert_eq!(vec!["e\u{301}", "🇩🇪", "x"], graphemes.iter().map(|g| &g[..]).collect::<Vec<_>>());
+        assert_eq!(Some(3..11), graphemes[1].range());
+        assert_eq!(3, a.grapheme_count());
+    }
+
+    #[test]
+    fn sub_graphemes() {
+        let a: SrcStr = "ab🇩🇪e\u{301}c".into();
+
+        assert_eq!("🇩🇪e\u{301}", &a.sub_graphemes(2..4)[..]);
+        assert_eq!("", &a.sub_graphemes(5..5)[..]);
+        assert_eq!(a.range(), a.sub_graphemes(0..5).range());
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_graphemes_out_of_bounds() {
+        let a: SrcStr = "🇩🇪".into();
+        a.sub_graphemes(0..2);
+    }
+}
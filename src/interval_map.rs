@@ -0,0 +1,217 @@
+use core::ops::Range;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, SrcStr};
+
+// values tagged with spans of one source, kept in an avl tree ordered by start.
+// every node also knows the furthest end in its subtree, so queries skip whole
+// subtrees that end before the offset and stay O(log n + k)
+pub struct IntervalMap<V, P: SharedOwner<str> = Rc<String>> {
+    src: SrcStr<P>,
+    nodes: Vec<Node<V, P>>,
+    root: Option<usize>,
+}
+
+struct Node<V, P: SharedOwner<str>> {
+    range: Range<usize>,
+    span: SrcStr<P>,
+    value: V,
+    left: Option<usize>,
+    right: Option<usize>,
+    height: u8,
+    max_end: usize,
+}
+
+impl<V, P: SharedOwner<str>> IntervalMap<V, P> {
+    pub fn new(src: SrcStr<P>) -> Self {
+        Self { src, nodes: Vec::new(), root: None }
+    }
+
+    pub fn src(&self) -> &SrcStr<P> {
+        &self.src
+    }
+
+    // hands the value back if the span isn't part of the source
+    pub fn insert(&mut self, span: SrcStr<P>, value: V) -> Result<(), V> {
+        let start = match span.offset_in(&self.src) {
+            Some(start) => start,
+            None => return Err(value),
+        };
+        let range = start..start + span.len();
+        let max_end = range.end;
+        self.nodes.push(Node { range, span, value, left: None, right: None, height: 1, max_end });
+        let new = self.nodes.len() - 1;
+        self.root = Some(self.insert_node(self.root, new));
+        Ok(())
+    }
+
+    // equal starts go right, so they stay in insertion order
+    fn insert_node(&mut self, at: Option<usize>, new: usize) -> usize {
+        let i = match at {
+            Some(i) => i,
+            None => return new,
+        };
+        if self.nodes[new].range.start < self.nodes[i].range.start {
+            self.nodes[i].left = Some(self.insert_node(self.nodes[i].left, new));
+        } else {
+            self.nodes[i].right = Some(self.insert_node(self.nodes[i].right, new));
+        }
+        self.rebalance(i)
+    }
+
+    fn height(&self, at: Option<usize>) -> u8 {
+        at.map_or(0, |i| self.nodes[i].height)
+    }
+
+    fn update(&mut self, i: usize) {
+        let (left, right) = (self.nodes[i].left, self.nodes[i].right);
+        let max_end = [left, right].iter().flatten().map(|&c| self.nodes[c].max_end).fold(self.nodes[i].range.end, usize::max);
+        self.nodes[i].height = 1 + self.height(left).max(self.height(right));
+        self.nodes[i].max_end = max_end;
+    }
+
+    fn rotate_left(&mut self, i: usize) -> usize {
+        let r = self.nodes[i].right.unwrap();
+        self.nodes[i].right = self.nodes[r].left;
+        self.update(i);
+        self.nodes[r].left = Some(i);
+        self.update(r);
+        r
+    }
+
+    fn rotate_right(&mut self, i: usize) -> usize {
+        let l = self.nodes[i].left.unwrap();
+        self.nodes[i].left = self.nodes[l].right;
+        self.update(i);
+        self.nodes[l].right = Some(i);
+        self.update(l);
+        l
+    }
+
+    fn rebalance(&mut self, i: usize) -> usize {
+        self.update(i);
+        let (left, right) = (self.nodes[i].left, self.nodes[i].right);
+        let (lh, rh) = (self.height(left), self.height(right));
+        if lh > rh + 1 {
+            let l = left.unwrap();
+            if self.height(self.nodes[l].left) < self.height(self.nodes[l].right) {
+                self.nodes[i].left = Some(self.rotate_left(l));
+            }
+            self.rotate_right(i)
+        } else if rh > lh + 1 {
+            let r = right.unwrap();
+            if self.height(self.nodes[r].right) < self.height(self.nodes[r].left) {
+                self.nodes[i].right = Some(self.rotate_right(r));
+            }
+            self.rotate_left(i)
+        } else {
+            i
+        }
+    }
+
+    // entries starting before `end` whose span ends after `start`, latest start first
+    fn query(&self, start: usize, end: usize) -> impl Iterator<Item = (&SrcStr<P>, &V)> + '_ {
+        // (node, whether its subtrees are already queued)
+        let mut stack: Vec<(usize, bool)> = self.root.map(|i| (i, false)).into_iter().collect();
+        core::iter::from_fn(move || loop {
+            let (i, expanded) = stack.pop()?;
+            let node = &self.nodes[i];
+            if expanded {
+                if node.range.end > start {
+                    return Some((&node.span, &node.value));
+                }
+                continue;
+            }
+            if node.max_end <= start {
+                continue;
+            }
+            stack.extend(node.left.map(|l| (l, false)));
+            if node.range.start < end {
+                stack.push((i, true));
+                stack.extend(node.right.map(|r| (r, false)));
+            }
+        })
+    }
+
+    // everything covering the byte at `offset` (relative to the source)
+    pub fn values_at(&self, offset: usize) -> impl Iterator<Item = (&SrcStr<P>, &V)> + '_ {
+        self.query(offset, offset + 1)
+    }
+
+    // everything sharing at least one byte with the span
+    pub fn overlapping(&self, span: &SrcStr<P>) -> impl Iterator<Item = (&SrcStr<P>, &V)> + '_ {
+        let range = span.offset_in(&self.src).map(|start| start..start + span.len());
+        let (start, end) = range.filter(|r| !r.is_empty()).map_or((0, 0), |r| (r.start, r.end));
+        self.query(start, end)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // in order of start
+    pub fn iter(&self) -> impl Iterator<Item = (&SrcStr<P>, &V)> + '_ {
+        let mut stack = Vec::new();
+        let mut at = self.root;
+        core::iter::from_fn(move || {
+            while let Some(i) = at {
+                stack.push(i);
+                at = self.nodes[i].left;
+            }
+            let node = &self.nodes[stack.pop()?];
+            at = node.right;
+            Some((&node.span, &node.value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn stabbing() {
+        let a: SrcStr = "fn f() { let x = g(1); }".into();
+        let mut map = IntervalMap::new(a.clone());
+        map.insert(a.sub(0..24), "item").unwrap();
+        map.insert(a.sub(7..24), "block").unwrap();
+        map.insert(a.sub(17..21), "call").unwrap();
+        map.insert(a.sub(13..14), "binding").unwrap();
+        assert_eq!(Err("other"), map.insert("x".into(), "other"));
+
+        let mut at: Vec<_> = map.values_at(18).map(|(_, v)| *v).collect();
+        at.sort();
+        assert_eq!(vec!["block", "call", "item"], at);
+        assert_eq!(vec!["item"], map.values_at(2).map(|(_, v)| *v).collect::<Vec<_>>());
+        assert_eq!(0, map.values_at(24).count());
+
+        let mut hits: Vec<_> = map.overlapping(&a.sub(12..18)).map(|(_, v)| *v).collect();
+        hits.sort();
+        assert_eq!(vec!["binding", "block", "call", "item"], hits);
+        assert_eq!(0, map.overlapping(&a.sub(14..14)).count());
+    }
+
+    #[test]
+    fn whole_source_first() {
+        let a: SrcStr = "x".repeat(4096).into();
+        let mut map = IntervalMap::new(a.clone());
+        map.insert(a.clone(), 0).unwrap();
+        for i in 0..1024 {
+            map.insert(a.sub(i * 4..i * 4 + 3), i + 1).unwrap();
+        }
+
+        // balanced, and nothing but the whole-file entry and the token reaches 2001
+        assert!(map.height(map.root) <= 15);
+        assert_eq!(vec![501, 0], map.values_at(2001).map(|(_, v)| *v).collect::<Vec<_>>());
+        assert_eq!(vec![0], map.values_at(2003).map(|(_, v)| *v).collect::<Vec<_>>());
+        assert_eq!(map.len(), map.iter().count());
+        assert!(map.iter().map(|(s, _)| s.range().unwrap().start).is_sorted());
+    }
+}
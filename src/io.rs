@@ -0,0 +1,60 @@
+use std::io::{self, BufRead, Read};
+use std::string::String;
+
+use crate::{SharedOwner, Src};
+
+impl<P: SharedOwner<str> + From<String>> Src<str, P> {
+    // reads straight into the buffer that becomes the owner
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn from_reader<R: Read>(reader: R) -> io::Result<Self> {
+        Self::from_reader_with_capacity(reader, 0)
+    }
+
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn from_reader_with_capacity<R: Read>(mut reader: R, capacity: usize) -> io::Result<Self> {
+        let mut text = String::with_capacity(capacity);
+        reader.read_to_string(&mut text)?;
+        Ok(text.into())
+    }
+
+    // a buffer says nothing about how long the whole input is, so this takes no
+    // capacity guess from it; use from_reader_with_capacity when the size is known
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn from_buf_read<R: BufRead>(reader: R) -> io::Result<Self> {
+        Self::from_reader(reader)
+    }
+
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader<R: tokio::io::AsyncRead + Unpin>(mut reader: R) -> io::Result<Self> {
+        use tokio::io::AsyncReadExt;
+
+        let mut text = String::new();
+        reader.read_to_string(&mut text).await?;
+        // async fns can't track their caller, so this records no provenance
+        Ok(Self::whole(P::from(text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RcSrcStr;
+
+    #[test]
+    fn from_reader() {
+        let a = RcSrcStr::from_reader(&b"The Gay Science"[..]).unwrap();
+        assert_eq!("The Gay Science", &a[..]);
+
+        let b = RcSrcStr::from_buf_read(std::io::BufReader::new(&b"Daybreak"[..])).unwrap();
+        assert_eq!("Daybreak", &b[..]);
+
+        assert!(RcSrcStr::from_reader(&b"\xff\xfe"[..]).is_err());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn from_async_reader() {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let a = runtime.block_on(RcSrcStr::from_async_reader(&b"Human, All Too Human"[..])).unwrap();
+        assert_eq!("Human, All Too Human", &a[..]);
+    }
+}
@@ -0,0 +1,577 @@
+use core::fmt;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{DerivedSrcStr, SharedOwner, SrcCursor, SrcStr, Trivia};
+
+// an error about a particular stretch of the source
+#[derive(Clone, PartialEq, Eq)]
+pub struct SpannedError<P: SharedOwner<str> = Rc<String>> {
+    pub span: SrcStr<P>,
+    pub message: String,
+}
+
+impl<P: SharedOwner<str>> SpannedError<P> {
+    pub fn new(span: SrcStr<P>, message: impl Into<String>) -> Self {
+        Self { span, message: message.into() }
+    }
+}
+
+// by hand, since a derive would want P: Debug
+impl<P: SharedOwner<str>> fmt::Debug for SpannedError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpannedError").field("span", &self.span).field("message", &self.message).finish()
+    }
+}
+
+impl<P: SharedOwner<str>> fmt::Display for SpannedError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, col) = self.span.line_col();
+        if let Some(name) = self.span.name() {
+            write!(f, "{}:", name)?;
+        }
+        write!(f, "{}:{}: {}", line, col, self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P: SharedOwner<str>> std::error::Error for SpannedError<P> {}
+
+// which number syntaxes take_number accepts. the prefixes are 0x, 0o and 0b
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberOptions {
+    pub underscores: bool,
+    pub hex: bool,
+    pub octal: bool,
+    pub binary: bool,
+    pub floats: bool,
+    pub exponents: bool,
+}
+
+impl Default for NumberOptions {
+    fn default() -> Self {
+        Self { underscores: true, hex: true, octal: true, binary: true, floats: true, exponents: true }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberKind {
+    Integer { radix: u32 },
+    Float,
+}
+
+// the whole literal, and just its digits (the literal without any prefix)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NumberToken<P: SharedOwner<str> = Rc<String>> {
+    pub text: SrcStr<P>,
+    pub digits: SrcStr<P>,
+    pub kind: NumberKind,
+}
+
+// how a string literal spells characters it can't hold directly
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Escapes {
+    // no escapes; the literal ends at the first closing quote
+    None,
+    // \n \r \t \0 \\ \' \" \xHH and \u{H..}
+    Backslash,
+    // the quote written twice, as in sql and pascal
+    Doubled,
+}
+
+// what take_balanced looks past: delimiters inside these strings and
+// comments don't count
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Balance<'a> {
+    pub quotes: &'a [(char, Escapes)],
+    pub comments: Option<Trivia<'a>>,
+}
+
+// one escape sequence, and the char it stands for. None if it's malformed,
+// so every bad escape in a literal can be reported, not just the first
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Escape<P: SharedOwner<str> = Rc<String>> {
+    pub span: SrcStr<P>,
+    pub value: Option<char>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StringLiteral<P: SharedOwner<str> = Rc<String>> {
+    // quotes included
+    pub text: SrcStr<P>,
+    // with escapes resolved; bad ones are kept as written
+    pub value: String,
+    pub escapes: Vec<Escape<P>>,
+}
+
+impl<P: SharedOwner<str>> StringLiteral<P> {
+    pub fn bad_escapes(&self) -> impl Iterator<Item = &Escape<P>> + '_ {
+        self.escapes.iter().filter(|escape| escape.value.is_none())
+    }
+}
+
+// the char a backslash escape stands for, and how long it is (backslash
+// included). None for the value if it's malformed
+fn backslash_escape(text: &str) -> (Option<char>, usize) {
+    let mut chars = text[1..].chars();
+    let c = match chars.next() {
+        Some(c) => c,
+        None => return (None, 1),
+    };
+    let simple = match c {
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        '0' => Some('\0'),
+        '\\' | '\'' | '"' => Some(c),
+        _ => None,
+    };
+    if simple.is_some() {
+        return (simple, 2);
+    }
+    match c {
+        'x' => {
+            let hex = text.get(2..4).filter(|h| h.bytes().all(|b| b.is_ascii_hexdigit()));
+            match hex {
+                Some(hex) => (u8::from_str_radix(hex, 16).ok().filter(u8::is_ascii).map(char::from), 4),
+                None => (None, 2),
+            }
+        },
+        'u' if text[2..].starts_with('{') => {
+            // at most six digits, so a missing } can't run past the literal
+            let digits = text[3..].bytes().take(6).take_while(u8::is_ascii_hexdigit).count();
+            if digits > 0 && text[3 + digits..].starts_with('}') {
+                (u32::from_str_radix(&text[3..3 + digits], 16).ok().and_then(char::from_u32), 4 + digits)
+            } else {
+                (None, 3 + digits)
+            }
+        },
+        _ => (None, 1 + c.len_utf8()),
+    }
+}
+
+// the lines of an indented block, and all of them as one slice (without the
+// final line break)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndentedBlock<P: SharedOwner<str> = Rc<String>> {
+    pub text: SrcStr<P>,
+    pub lines: Vec<SrcStr<P>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Heredoc<P: SharedOwner<str> = Rc<String>> {
+    // whatever followed the opener on its line, for the caller to lex
+    pub trailing: SrcStr<P>,
+    // the body as written, without the terminator line
+    pub text: SrcStr<P>,
+    // in dedent mode, the body with common indentation removed, mapped back
+    // line by line to `text`
+    pub dedented: Option<DerivedSrcStr<P>>,
+    pub terminator: SrcStr<P>,
+}
+
+// leading spaces and tabs, each counting one; None for a blank line
+fn indent(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start_matches([' ', '\t']);
+    (!trimmed.trim().is_empty()).then(|| line.len() - trimmed.len())
+}
+
+impl<P: SharedOwner<str>> SrcCursor<P> {
+    // a number literal at the front. anything after it (a suffix, a stray
+    // letter) is left for the caller. consumes nothing on an error
+    pub fn take_number(&mut self, options: NumberOptions) -> Result<NumberToken<P>, SpannedError<P>> {
+        let start = self.checkpoint();
+        let result = self.number(options);
+        if result.is_err() {
+            self.reset(start);
+        }
+        result
+    }
+
+    fn number(&mut self, options: NumberOptions) -> Result<NumberToken<P>, SpannedError<P>> {
+        let origin = self.rest().clone();
+        if !origin.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(SpannedError::new(origin.sub(0..0), "expected a number"));
+        }
+
+        let prefixes = [(options.hex, 16, "0x"), (options.octal, 8, "0o"), (options.binary, 2, "0b")];
+        let radix_prefix = prefixes.iter().find(|&&(on, _, prefix)| {
+            on && origin.get(0..2).is_some_and(|s| s.eq_ignore_ascii_case(prefix))
+        });
+        if let Some(&(_, radix, _)) = radix_prefix {
+            let prefix = self.advance(2);
+            let digits = self.digits(radix, options);
+            if !digits.contains(|c: char| c != '_') {
+                return Err(SpannedError::new(prefix, "no digits after the radix prefix"));
+            }
+            let text = self.rest().consumed_since(&origin);
+            return Ok(NumberToken { text, digits, kind: NumberKind::Integer { radix } });
+        }
+
+        let mut kind = NumberKind::Integer { radix: 10 };
+        self.digits(10, options);
+        let rest = self.rest().clone();
+        if options.floats && rest.starts_with('.') && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            self.advance(1);
+            self.digits(10, options);
+            kind = NumberKind::Float;
+        }
+        // only an exponent once a digit follows the e (and sign), so `1em` is
+        // the integer 1 and `em` is left over
+        let rest = self.rest().clone();
+        let after_e = rest.strip_prefix(['e', 'E']).map(|s| s.strip_prefix(['+', '-']).unwrap_or(s));
+        if options.exponents && after_e.is_some_and(|s| s.starts_with(|c: char| c.is_ascii_digit())) {
+            self.advance(rest.len() - after_e.map_or(0, str::len));
+            self.digits(10, options);
+            kind = NumberKind::Float;
+        }
+        let text = self.rest().consumed_since(&origin);
+        Ok(NumberToken { digits: text.clone(), text, kind })
+    }
+
+    // a literal between two `quote`s, escapes resolved. an unterminated one
+    // is an error spanning to the end, and consumes nothing
+    pub fn take_string_literal(&mut self, quote: char, escapes: Escapes) -> Result<StringLiteral<P>, SpannedError<P>> {
+        let origin = self.rest().clone();
+        let text: &str = &origin;
+        if !text.starts_with(quote) {
+            return Err(SpannedError::new(origin.sub(0..0), alloc::format!("expected a string starting with {}", quote)));
+        }
+
+        let (mut value, mut found) = (String::new(), Vec::new());
+        let mut at = quote.len_utf8();
+        loop {
+            let rest = &text[at..];
+            let c = match rest.chars().next() {
+                Some(c) => c,
+                None => return Err(SpannedError::new(origin.clone(), "unterminated string")),
+            };
+            if c == quote && escapes == Escapes::Doubled && rest[c.len_utf8()..].starts_with(quote) {
+                value.push(quote);
+                found.push(Escape { span: origin.sub(at..at + 2 * c.len_utf8()), value: Some(quote) });
+                at += 2 * c.len_utf8();
+            } else if c == quote {
+                at += c.len_utf8();
+                break;
+            } else if c == '\\' && escapes == Escapes::Backslash {
+                let (escaped, len) = backslash_escape(rest);
+                let len = len.min(rest.len());
+                match escaped {
+                    Some(escaped) => value.push(escaped),
+                    None => value.push_str(&rest[..len]),
+                }
+                found.push(Escape { span: origin.sub(at..at + len), value: escaped });
+                at += len;
+            } else {
+                value.push(c);
+                at += c.len_utf8();
+            }
+        }
+        Ok(StringLiteral { text: self.advance(at), value, escapes: found })
+    }
+
+    // from an `open` at the front through its matching `close`, nesting,
+    // giving back what's between them. consumes nothing on an error
+    pub fn take_balanced(&mut self, open: &str, close: &str, balance: &Balance<'_>) -> Result<SrcStr<P>, SpannedError<P>> {
+        let origin = self.rest().clone();
+        if open.is_empty() || !origin.starts_with(open) {
+            return Err(SpannedError::new(origin.sub(0..0), alloc::format!("expected {}", open)));
+        }
+        let comments = balance.comments.map(|comments| Trivia { whitespace: false, ..comments });
+
+        let mut inner = SrcCursor::new(origin.clone());
+        inner.advance(open.len());
+        let mut depth = 1;
+        loop {
+            if let Some(comments) = &comments {
+                if !inner.skip_trivia(comments).is_empty() {
+                    continue;
+                }
+            }
+            let rest = inner.rest().clone();
+            if let Some(&(quote, escapes)) = balance.quotes.iter().find(|(quote, _)| rest.starts_with(*quote)) {
+                inner.take_string_literal(quote, escapes)?;
+            } else if rest.starts_with(close) {
+                depth -= 1;
+                if depth == 0 {
+                    let body = rest.consumed_since(&origin).sub(open.len()..);
+                    self.advance(origin.len() - rest.len() + close.len());
+                    return Ok(body);
+                }
+                inner.advance(close.len());
+            } else if rest.starts_with(open) {
+                depth += 1;
+                inner.advance(open.len());
+            } else if inner.take_char().is_none() {
+                return Err(SpannedError::new(origin.sub(..open.len()), alloc::format!("unclosed {}", open)));
+            }
+        }
+    }
+
+    // the lines after the current one that are indented deeper than it, as
+    // in python or yaml. blank lines inside the block belong to it, trailing
+    // ones don't. None, consuming nothing, if something other than whitespace
+    // is left on the current line, or nothing deeper follows it
+    pub fn take_indented_block(&mut self) -> Option<IndentedBlock<P>> {
+        let rest = self.rest().clone();
+        let current = indent(&rest.sub(0..0).containing_lines()).unwrap_or(0);
+        let first = rest.find('\n')? + 1;
+        if !rest[..first].trim().is_empty() {
+            return None;
+        }
+
+        let mut lines = Vec::new();
+        let mut kept = 0;
+        for line in rest.sub(first..).lines() {
+            match indent(&line) {
+                Some(depth) if depth <= current => break,
+                Some(_) => {
+                    lines.push(line);
+                    kept = lines.len();
+                },
+                None => lines.push(line),
+            }
+        }
+        lines.truncate(kept);
+
+        let last = lines.last()?;
+        let end = last.offset_in(&rest)? + last.len();
+        self.advance(end);
+        Some(IndentedBlock { text: rest.sub(first..end), lines })
+    }
+
+    // a heredoc body, from the line after the cursor up to a line holding
+    // just `terminator`. with `dedent` (ruby's <<~) the terminator can be
+    // indented, and the body's common indentation is stripped. consumes
+    // through the terminator, or nothing on an error
+    pub fn take_heredoc(&mut self, terminator: &str, dedent: bool) -> Result<Heredoc<P>, SpannedError<P>> {
+        let rest = self.rest().clone();
+        let first = rest.find('\n').map_or(rest.len(), |i| i + 1);
+
+        let mut at = first;
+        let end = loop {
+            if at >= rest.len() {
+                return Err(SpannedError::new(rest.sub(0..0), alloc::format!("heredoc is missing its {} line", terminator)));
+            }
+            let line_end = rest[at..].find('\n').map_or(rest.len(), |i| at + i);
+            let line = rest[at..line_end].trim_end_matches('\r');
+            let line = if dedent { line.trim_start_matches([' ', '\t']) } else { line };
+            if line == terminator {
+                break at;
+            }
+            at = line_end + 1;
+        };
+
+        let text = rest.sub(first..end);
+        let dedented = dedent.then(|| {
+            let strip = text.lines().filter_map(|line| indent(&line)).min().unwrap_or(0);
+            let (mut out, mut mapping, mut from) = (String::new(), Vec::new(), 0);
+            for line in text.split_inclusive('\n') {
+                let skip = (line.len() - line.trim_start_matches([' ', '\t']).len()).min(strip);
+                mapping.push((out.len()..out.len() + line.len() - skip, from + skip..from + line.len()));
+                out.push_str(&line[skip..]);
+                from += line.len();
+            }
+            text.derive(out, mapping)
+        });
+
+        let trailing = rest.sub(..first).trim_end_matches(['\r', '\n']).len();
+        let terminator_start = end + rest[end..].find(terminator).unwrap_or(0);
+        let heredoc = Heredoc {
+            trailing: rest.sub(..trailing),
+            text,
+            dedented,
+            terminator: rest.sub(terminator_start..terminator_start + terminator.len()),
+        };
+        self.advance(terminator_start + terminator.len());
+        Ok(heredoc)
+    }
+
+    fn digits(&mut self, radix: u32, options: NumberOptions) -> SrcStr<P> {
+        self.take_while(|c| c.is_digit(radix) || (options.underscores && c == '_'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use crate::RcSrcStr;
+
+    fn number(text: &'static str, options: NumberOptions) -> Result<(NumberKind, &'static str), SpannedError> {
+        let mut cursor = SrcCursor::new(RcSrcStr::from(text));
+        let token = cursor.take_number(options)?;
+        let len = token.text.len();
+        Ok((token.kind, &text[..len]))
+    }
+
+    #[test]
+    fn take_number() {
+        let all = NumberOptions::default();
+
+        assert_eq!(Ok((NumberKind::Integer { radix: 10 }, "1_000")), number("1_000;", all));
+        assert_eq!(Ok((NumberKind::Integer { radix: 16 }, "0xFF_ff")), number("0xFF_ffg", all));
+        assert_eq!(Ok((NumberKind::Integer { radix: 2 }, "0B101")), number("0B1012", all));
+        assert_eq!(Ok((NumberKind::Float, "3.14e-2")), number("3.14e-2", all));
+        assert_eq!(Ok((NumberKind::Integer { radix: 10 }, "1")), number("1.foo()", all));
+        assert_eq!(Ok((NumberKind::Float, "2E10")), number("2E10", all));
+        assert_eq!(Ok((NumberKind::Integer { radix: 10 }, "1")), number("1em", all));
+        assert_eq!(Ok((NumberKind::Integer { radix: 10 }, "1")), number("1e", all));
+        assert_eq!(Ok((NumberKind::Integer { radix: 10 }, "3")), number("3else", all));
+        assert_eq!(Ok((NumberKind::Integer { radix: 10 }, "1")), number("1e+", all));
+        assert_eq!(Ok((NumberKind::Float, "1.5")), number("1.5e-x", all));
+
+        let plain = NumberOptions { underscores: false, hex: false, floats: false, ..all };
+        assert_eq!(Ok((NumberKind::Integer { radix: 10 }, "0")), number("0x1", plain));
+        assert_eq!(Ok((NumberKind::Integer { radix: 10 }, "1")), number("1_0", plain));
+        assert_eq!(Ok((NumberKind::Integer { radix: 10 }, "1")), number("1.5", plain));
+    }
+
+    #[test]
+    fn number_errors() {
+        let mut cursor = SrcCursor::new(RcSrcStr::from("x = 0x_;"));
+        cursor.advance(4);
+
+        let err = cursor.take_number(NumberOptions::default()).unwrap_err();
+        assert_eq!(Some(4..6), err.span.range());
+        assert_eq!("1:5: no digits after the radix prefix", err.to_string());
+        assert_eq!(Some(4..8), cursor.rest().range());
+
+        assert_eq!("1:1: expected a number", number("-1", NumberOptions::default()).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn take_string_literal() {
+        let mut cursor = SrcCursor::new(RcSrcStr::from(r#""a\"b\n\u{e9}\q\x41" rest"#));
+        let literal = cursor.take_string_literal('"', Escapes::Backslash).unwrap();
+
+        assert_eq!("a\"b\né\\qA", literal.value);
+        assert_eq!(Some(0..20), literal.text.range());
+        assert_eq!(5, literal.escapes.len());
+        assert_eq!(Some(2..4), literal.escapes[0].span.range());
+        let bad: Vec<_> = literal.bad_escapes().map(|e| e.span.range()).collect();
+        assert_eq!(alloc::vec![Some(13..15)], bad);
+        assert_eq!(" rest", &cursor.rest()[..]);
+
+        let mut open_brace = SrcCursor::new(RcSrcStr::from(r#""\u{zz" + f(x) }"#));
+        let literal = open_brace.take_string_literal('"', Escapes::Backslash).unwrap();
+        assert_eq!(("\\u{zz", Some(1..4)), (&literal.value[..], literal.escapes[0].span.range()));
+        assert_eq!(Some(None), literal.escapes.first().map(|e| e.value));
+        assert_eq!(" + f(x) }", &open_brace.rest()[..]);
+    }
+
+    #[test]
+    fn string_literal_styles() {
+        let mut sql = SrcCursor::new(RcSrcStr::from("'it''s' x"));
+        let literal = sql.take_string_literal('\'', Escapes::Doubled).unwrap();
+        assert_eq!(("it's", "'it''s'"), (&literal.value[..], &literal.text[..]));
+
+        let mut raw = SrcCursor::new(RcSrcStr::from(r#""a\" b"#));
+        assert_eq!("a\\", raw.take_string_literal('"', Escapes::None).unwrap().value);
+
+        let mut open = SrcCursor::new(RcSrcStr::from(r#""abc\""#));
+        let err = open.take_string_literal('"', Escapes::Backslash).unwrap_err();
+        assert_eq!((Some(0..6), "unterminated string"), (err.span.range(), &err.message[..]));
+        assert_eq!(Some(0..6), open.rest().range());
+    }
+
+    #[test]
+    fn take_balanced() {
+        let mut cursor = SrcCursor::new(RcSrcStr::from("(a (b) \")\" /* ) */ c) d"));
+        let aware = Balance { quotes: &[('"', Escapes::Backslash)], comments: Some(Trivia::C) };
+
+        let body = cursor.take_balanced("(", ")", &aware).unwrap();
+        assert_eq!(("a (b) \")\" /* ) */ c", Some(1..20)), (&body[..], body.range()));
+        assert_eq!(" d", &cursor.rest()[..]);
+
+        let mut plain = SrcCursor::new(RcSrcStr::from("{{ x }} }}"));
+        assert_eq!(" x ", &plain.take_balanced("{{", "}}", &Balance::default()).unwrap()[..]);
+    }
+
+    #[test]
+    fn unbalanced() {
+        let mut cursor = SrcCursor::new(RcSrcStr::from("[a [b]"));
+        let err = cursor.take_balanced("[", "]", &Balance::default()).unwrap_err();
+        assert_eq!((Some(0..1), "unclosed ["), (err.span.range(), &err.message[..]));
+        assert!(cursor.take_balanced("(", ")", &Balance::default()).is_err());
+        assert_eq!(Some(0..6), cursor.rest().range());
+
+        let mut quoted = SrcCursor::new(RcSrcStr::from("[a \"]"));
+        let aware = Balance { quotes: &[('"', Escapes::None)], ..Balance::default() };
+        assert_eq!("unterminated string", quoted.take_balanced("[", "]", &aware).unwrap_err().message);
+        assert_eq!("a \"", &quoted.take_balanced("[", "]", &Balance::default()).unwrap()[..]);
+    }
+
+    #[test]
+    fn take_indented_block() {
+        let a: SrcStr = "def f():\n    x = 1\n\n      y\n    z\n\nprint(f)".into();
+        let mut cursor = SrcCursor::new(a.clone());
+        cursor.advance(8);
+
+        let block = cursor.take_indented_block().unwrap();
+        assert_eq!("    x = 1\n\n      y\n    z", &block.text[..]);
+        let lines: Vec<_> = block.lines.iter().map(|l| &l[..]).collect();
+        assert_eq!(alloc::vec!["    x = 1", "", "      y", "    z"], lines);
+        assert_eq!("\n\nprint(f)", &cursor.rest()[..]);
+        assert!(cursor.take_indented_block().is_none());
+    }
+
+    #[test]
+    fn nested_block() {
+        let a: SrcStr = "a:\n  b:\n    c\n  d".into();
+        let mut cursor = SrcCursor::new(a);
+        cursor.advance(6);
+
+        assert!(cursor.take_indented_block().is_none());
+        cursor.advance(1);
+        assert_eq!(Some(8..13), cursor.take_indented_block().map(|b| b.text.range().unwrap()));
+        assert_eq!("\n  d", &cursor.rest()[..]);
+
+        let mut literal = SrcCursor::new(RcSrcStr::from_static("x\n y\n z"));
+        literal.advance(1);
+        assert_eq!(" y\n z", &literal.take_indented_block().unwrap().text[..]);
+    }
+
+    #[test]
+    fn take_heredoc() {
+        let a: SrcStr = "x = <<EOS.strip\n  a\n b\nEOS\ny".into();
+        let mut cursor = SrcCursor::new(a);
+        cursor.advance(9);
+
+        let heredoc = cursor.take_heredoc("EOS", false).unwrap();
+        assert_eq!(".strip", &heredoc.trailing[..]);
+        assert_eq!("  a\n b\n", &heredoc.text[..]);
+        assert!(heredoc.dedented.is_none());
+        assert_eq!(Some(23..26), heredoc.terminator.range());
+        assert_eq!("\ny", &cursor.rest()[..]);
+    }
+
+    #[test]
+    fn dedented_heredoc() {
+        let a: SrcStr = "<<~SQL\n    select *\n\n      from t\n  SQL".into();
+        let mut cursor = SrcCursor::new(a);
+        cursor.advance(6);
+
+        let heredoc = cursor.take_heredoc("SQL", true).unwrap();
+        assert_eq!("", &heredoc.trailing[..]);
+        let dedented = heredoc.dedented.unwrap();
+        assert_eq!("select *\n\n  from t\n", &dedented[..]);
+        let from = dedented.sub(12..16).origin_span().unwrap();
+        assert_eq!(("from", Some(27..31)), (&from[..], from.range()));
+        assert!(cursor.is_empty());
+
+        let mut open = SrcCursor::new(RcSrcStr::from("<<EOS\nbody\n  EOS"));
+        assert!(open.take_heredoc("EOS", false).is_err());
+        assert_eq!(Some(0..16), open.rest().range());
+    }
+
+    #[test]
+    fn digits() {
+        let mut cursor = SrcCursor::new(RcSrcStr::from("0o17"));
+        let token = cursor.take_number(NumberOptions::default()).unwrap();
+
+        assert_eq!(("0o17", "17"), (&token.text[..], &token.digits[..]));
+        assert_eq!(Ok(15), u32::from_str_radix(&token.digits, 8));
+    }
+}
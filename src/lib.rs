@@ -1,196 +1,516 @@
 #![feature(try_trait)]
+#![no_std]
 
+extern crate alloc;
+// so derive output, which names ::srcstr, works in this crate's own tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as srcstr;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+use core::any::Any;
 use core::hash::Hash;
 use core::hash::Hasher;
 use core::ops::Deref;
 use core::ops::Index;
-use core::ops::{Range, RangeFrom, RangeTo, RangeFull, RangeInclusive, RangeToInclusive };
-use std::rc::Rc;
+use core::ops::Range;
+use core::ops::RangeBounds;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
 use core::fmt;
 use core::ops::Try;
 
-#[derive(Clone)]
-pub struct SrcStr {
-    rc: Rc<String>,
-    ptr: *const str, // either points into the owner, or 'static
+use provenance::Provenance;
+
+mod bytes;
+#[cfg(feature = "std")]
+mod cache;
+mod chars;
+mod chunked;
+mod cursor;
+mod derived;
+mod detach;
+mod edits;
+mod embedded;
+#[cfg(feature = "encoding")]
+mod encoding;
+#[cfg(all(feature = "ffi", target_has_atomic = "ptr"))]
+pub mod ffi;
+mod fixture;
+#[cfg(feature = "unicode-segmentation")]
+mod graphemes;
+mod interval_map;
+#[cfg(feature = "std")]
+mod io;
+mod line_index;
+mod lex;
+mod lines;
+mod meta;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod named;
+mod newlines;
+#[cfg(feature = "std")]
+mod os;
+mod overlay;
+mod owner;
+mod pattern;
+mod provenance;
+mod range;
+#[cfg(feature = "regex")]
+mod regex;
+mod registry;
+mod relations;
+mod search;
+mod slice;
+mod small;
+mod source_map;
+mod span;
+mod span_set;
+mod spanned;
+mod split;
+#[cfg(feature = "text-size")]
+mod text_size;
+mod utf8;
+#[cfg(feature = "bytes")]
+mod utf8_bytes;
+mod versioned;
+mod weak;
+pub use bytes::SrcBytes;
+#[cfg(feature = "std")]
+pub use cache::SourceCache;
+#[cfg(feature = "notify")]
+pub use cache::CacheWatcher;
+pub use chunked::{AppendSource, ChunkedSource};
+pub use cursor::{ExpectedError, Furthest, Mark, SrcCursor, Trivia};
+pub use derived::{Derived, DerivedSrcStr};
+pub use detach::{DetachPolicy, Detached, Detaching};
+pub use edits::{EditError, SpanRemapper, TextEdit};
+pub use embedded::{Embedded, EmbeddedSrcStr, Site};
+#[cfg(feature = "encoding")]
+pub use encoding::SourceEncoding;
+pub use fixture::Fixture;
+pub use interval_map::IntervalMap;
+pub use lex::{Balance, Escape, Escapes, Heredoc, IndentedBlock, NumberKind, NumberOptions, NumberToken, SpannedError, StringLiteral};
+pub use line_index::{CharIndex, Indexed, LineIndex};
+pub use meta::WithMeta;
+#[cfg(feature = "mmap")]
+pub use mmap::MappedStr;
+pub use named::NamedSrc;
+pub use newlines::{Normalized, NormalizedSrcStr};
+#[cfg(feature = "std")]
+pub use os::{SrcOsStr, SrcPath};
+pub use overlay::{Overlay, OverlayBuilder};
+pub use owner::{SharedOwner, SourceBacking};
+pub use pattern::SrcPattern;
+pub use range::RangeError;
+#[cfg(feature = "regex")]
+pub use crate::regex::Captures;
+pub use registry::{SourceId, SourceRegistry};
+pub use slice::Slice;
+pub use small::SmallSrcStr;
+pub use source_map::{Loc, SourceMap};
+pub use span::Span;
+pub use span_set::SpanSet;
+pub use spanned::{HasSpan, Spanned};
+#[cfg(feature = "derive")]
+pub use srcstr_derive::HasSpan;
+pub use utf8::Utf8SpanError;
+#[cfg(feature = "bytes")]
+pub use utf8_bytes::Utf8Bytes;
+pub use versioned::{VersionedSource, VersionedSrcStr, VersionedText};
+pub use weak::{WeakOwner, WeakSrc, WeakSrcStr};
+
+pub type SrcStr<P = Rc<String>> = Src<str, P>;
+pub type RcSrcStr = SrcStr<Rc<String>>;
+#[cfg(target_has_atomic = "ptr")]
+pub type SyncSrcStr = SrcStr<Arc<String>>;
+
+enum Repr<T: ?Sized + 'static> {
+    Range { start: usize, len: usize }, // element range into the owner
+    Static(&'static T),
 }
 
-impl PartialEq for SrcStr {
+impl<T: ?Sized> Clone for Repr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T: ?Sized> Copy for Repr<T> {}
+
+impl<T: ?Sized> PartialEq for Repr<T> {
     fn eq(&self, rhs: &Self) -> bool {
-        self.rc.as_ptr() == rhs.rc.as_ptr() && self.ptr == rhs.ptr
+        match (self, rhs) {
+            (Repr::Range { start, len }, Repr::Range { start: rstart, len: rlen }) => start == rstart && len == rlen,
+            (Repr::Static(s), Repr::Static(r)) => core::ptr::eq(*s, *r),
+            _ => false,
+        }
+    }
+}
+
+// the owner pointer is non-null and Repr has spare tag values, so
+// Option<Src> costs nothing extra (see the niche test)
+pub struct Src<T: ?Sized + Slice, P: SharedOwner<T> = Rc<<T as Slice>::Owned>> {
+    rc: Option<P>, // None only for slices made by from_static
+    repr: Repr<T>,
+    created: Provenance,
+}
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Clone for Src<T, P> {
+    fn clone(&self) -> Self {
+        Self { rc: self.rc.clone(), repr: self.repr, created: self.created }
     }
 }
-impl Eq for SrcStr {}
 
-impl Hash for SrcStr {
+impl<T: ?Sized + Slice, P: SharedOwner<T>> PartialEq for Src<T, P> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.src_ptr() == rhs.src_ptr() && self.repr == rhs.repr
+    }
+}
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Eq for Src<T, P> {}
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Hash for Src<T, P> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.rc.as_ptr().hash(state);
-        self.ptr.hash(state);
+        self.src_ptr().hash(state);
+        match self.repr {
+            Repr::Range { start, len } => (start, len).hash(state),
+            Repr::Static(s) => (s as *const T).hash(state),
+        }
     }
 }
 
-impl fmt::Debug for SrcStr {
+impl<P: SharedOwner<str>> fmt::Debug for Src<str, P> {
 	fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
 		// TODO: put context in here? (full line. maybe only with #? debug format)
+		if let (Some(name), Some(range)) = (self.name(), self.range()) {
+			write!(f, "{}:{:?}: ", name, range)?;
+		}
 		f.write_str(self.deref())
 	}
 }
 
-impl From<Rc<String>> for SrcStr {
-    fn from(rc: Rc<String>) -> Self {
-        let ptr = (&**rc) as *const str;
-        Self { rc, ptr }
+impl<E: fmt::Debug + 'static, P: SharedOwner<[E]>> fmt::Debug for Src<[E], P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.deref(), f)
     }
 }
 
-impl From<String> for SrcStr {
+impl<P: SharedOwner<str> + From<String>> From<String> for Src<str, P> {
+    #[cfg_attr(feature = "provenance", track_caller)]
     fn from(string: String) -> Self {
-        Rc::new(string).into()
+        Self { created: Provenance::here(), ..Self::whole(P::from(string)) }
     }
 }
 
-impl<'a> From<&'a str> for SrcStr {
+impl<'a, P: SharedOwner<str> + From<String>> From<&'a str> for Src<str, P> {
+	#[cfg_attr(feature = "provenance", track_caller)]
 	fn from(string: &'a str) -> Self {
 		string.to_string().into()
 	}
 }
 
-impl Deref for SrcStr {
-    type Target = str;
-    fn deref(&self) -> &str {
-        unsafe { &*self.ptr }
+impl<E: 'static, P: SharedOwner<[E]> + From<Vec<E>>> From<Vec<E>> for Src<[E], P> {
+    fn from(elems: Vec<E>) -> Self {
+        Self::whole(P::from(elems))
     }
 }
 
-impl From<SrcStr> for String {
-    fn from(ss: SrcStr) -> String {
-        (*ss).to_string()
+// a Box<str> becomes a String without copying, so Rc<String> owners adopt the text as is
+impl<P: SharedOwner<str> + From<String>> From<Box<str>> for Src<str, P> {
+    #[cfg_attr(feature = "provenance", track_caller)]
+    fn from(string: Box<str>) -> Self {
+        String::from(string).into()
     }
 }
 
-impl Index<Range<usize>> for SrcStr {
-    type Output = str;
-    fn index(&self, index: Range<usize>) -> &Self::Output {
-        &self.deref()[index]
+impl<E: 'static, P: SharedOwner<[E]> + From<Vec<E>>> From<Box<[E]>> for Src<[E], P> {
+    fn from(elems: Box<[E]>) -> Self {
+        Vec::from(elems).into()
     }
 }
 
-impl Index<RangeFrom<usize>> for SrcStr {
-    type Output = str;
-    fn index(&self, index: RangeFrom<usize>) -> &Self::Output {
-        &self.deref()[index]
+// borrowed text is 'static, so it doesn't need an owner at all
+impl<T, P> From<Cow<'static, T>> for Src<T, P>
+where
+    T: ?Sized + Slice + ToOwned,
+    P: SharedOwner<T> + From<<T as ToOwned>::Owned>,
+{
+    fn from(cow: Cow<'static, T>) -> Self {
+        match cow {
+            Cow::Borrowed(s) => Self::from_static(s),
+            Cow::Owned(owned) => Self::whole(P::from(owned)),
+        }
     }
 }
 
-impl Index<RangeTo<usize>> for SrcStr {
-    type Output = str;
-    fn index(&self, index: RangeTo<usize>) -> &Self::Output {
-        &self.deref()[index]
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Default for Src<T, P> {
+    fn default() -> Self {
+        Self::empty()
     }
 }
 
-impl Index<RangeInclusive<usize>> for SrcStr {
-    type Output = str;
-    fn index(&self, index: RangeInclusive<usize>) -> &Self::Output {
-        &self.deref()[index]
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Deref for Src<T, P> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        self.validate();
+        match (&self.rc, self.repr) {
+            // the range is checked against the owner whenever it's set
+            (Some(rc), Repr::Range { start, len }) => unsafe {
+                T::from_elems_unchecked(rc.elems().get_unchecked(start..start + len))
+            },
+            (None, Repr::Range { .. }) => unreachable!(),
+            (_, Repr::Static(s)) => s,
+        }
     }
 }
 
-impl Index<RangeToInclusive<usize>> for SrcStr {
-    type Output = str;
-    fn index(&self, index: RangeToInclusive<usize>) -> &Self::Output {
-        &self.deref()[index]
+impl<P: SharedOwner<str>> From<Src<str, P>> for String {
+    fn from(ss: Src<str, P>) -> String {
+        (*ss).to_string()
     }
 }
 
-impl Index<RangeFull> for SrcStr {
-    type Output = str;
-    fn index(&self, index: RangeFull) -> &Self::Output {
+macro_rules! into_string {
+    ($($rc:ident),*) => {$(
+        impl Src<str, $rc<String>> {
+            // hands back the original String when this is all of it, and nothing else holds it
+            pub fn into_string(self) -> String {
+                match (self.rc, self.repr) {
+                    (Some(rc), Repr::Range { start: 0, len }) if len == rc.len() => {
+                        $rc::try_unwrap(rc).unwrap_or_else(|rc| (*rc).clone())
+                    },
+                    (rc, repr) => Src { rc, repr, created: self.created }.to_string(),
+                }
+            }
+        }
+    )*};
+}
+
+into_string!(Rc);
+#[cfg(target_has_atomic = "ptr")]
+into_string!(Arc);
+
+impl<T, P, I> Index<I> for Src<T, P>
+where
+    T: ?Sized + Slice + Index<I>,
+    P: SharedOwner<T>,
+{
+    type Output = T::Output;
+    fn index(&self, index: I) -> &Self::Output {
         &self.deref()[index]
     }
 }
 
-impl SrcStr {
-    pub fn src(&self) -> &Rc<String> {
-        &self.rc
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Src<T, P> {
+    fn whole(rc: P) -> Self {
+        let len = rc.elems().len();
+        Self { rc: Some(rc), repr: Repr::Range { start: 0, len }, created: Provenance::NONE }
     }
 
-    pub fn try_run<T, F>(&mut self, f: F) -> T
+    // const, so keyword tables can live in consts (or statics, for Sync owners)
+    pub const fn from_static(s: &'static T) -> Self {
+        Self { rc: None, repr: Repr::Static(s), created: Provenance::NONE }
+    }
+
+    // backed by one shared empty literal, so placeholders never allocate
+    pub fn empty() -> Self {
+        Self::from_static(T::empty())
+    }
+
+    fn src_ptr(&self) -> *const T::Elem {
+        self.rc.as_ref().map_or(core::ptr::null(), |rc| rc.elems().as_ptr())
+    }
+
+    pub fn as_cow(&self) -> Cow<'_, T>
     where
-        F: FnOnce(&mut Self) -> T,
-        T: Try,
+        T: ToOwned,
     {
-        let ptr = self.ptr;
+        Cow::Borrowed(self)
+    }
+
+    pub fn src(&self) -> Option<&P> {
+        self.rc.as_ref()
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.rc.as_ref().and_then(|rc| rc.name())
+    }
+
+    pub fn meta<M: Any>(&self) -> Option<&M> {
+        self.rc.as_ref().and_then(|rc| rc.meta()).and_then(|meta| meta.downcast_ref())
+    }
+
+    pub fn try_run<R, F>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+        R: Try,
+    {
+        let repr = self.repr;
         match f(self).into_result() {
-            Ok(ok) => T::from_ok(ok),
+            Ok(ok) => R::from_ok(ok),
             Err(err) => {
-                self.ptr = ptr;
-                T::from_error(err)
+                self.repr = repr;
+                R::from_error(err)
             },
         }
     }
 
-    pub fn edit<T, F>(&mut self, f: F) -> T
+    pub fn edit<R, F>(&mut self, f: F) -> R
     where
-        F: FnOnce(&mut &str) -> T,
+        F: FnOnce(&mut &T) -> R,
     {
         let mut s = &**self;
         let result = f(&mut s);
-        self.ptr = s as *const str;
+
+        // anything the closure can hand back is either inside the owner, or 'static
+        self.repr = match self.offset_of(s) {
+            Some(start) => Repr::Range { start, len: s.as_elems().len() },
+            None => Repr::Static(unsafe { &*(s as *const T) }),
+        };
+        self.validate();
 
         result
     }
 
-    pub fn try_edit<T, E, F>(&mut self, f: F) -> Result<T, E>
+    // where `piece` starts in the owner, if it lies inside it at all
+    fn offset_of(&self, piece: &T) -> Option<usize> {
+        let elems = piece.as_elems();
+        let size = core::mem::size_of::<T::Elem>().max(1);
+        let ptr = elems.as_ptr() as usize;
+        self.rc.as_ref().map(|rc| rc.elems()).and_then(|outer| {
+            let start = outer.as_ptr() as usize;
+            if ptr >= start && ptr + elems.len() * size <= start + outer.len() * size {
+                Some((ptr - start) / size)
+            } else {
+                None
+            }
+        })
+    }
+
+    // turns a &T borrowed out of this source back into a Src, if it's inside the owner
+    pub fn slice_ref(&self, subset: &T) -> Option<Self> {
+        let start = self.offset_of(subset)?;
+        Some(Self { rc: self.rc.clone(), repr: Repr::Range { start, len: subset.as_elems().len() }, created: self.created })
+    }
+
+    // like slice_ref, but also works on 'static slices. `piece` must come from
+    // this slice's own text
+    fn subslice(&self, piece: &T) -> Self {
+        let (outer, inner) = (self.as_elems().as_ptr_range(), piece.as_elems().as_ptr_range());
+        assert!(outer.start <= inner.start && inner.end <= outer.end, "piece is not part of this Src");
+        match self.repr {
+            Repr::Range { .. } => self.slice_ref(piece).unwrap(),
+            Repr::Static(_) => {
+                // inside a 'static slice, so 'static too
+                let piece = unsafe { &*(piece as *const T) };
+                Self { rc: self.rc.clone(), repr: Repr::Static(piece), created: self.created }
+            },
+        }
+    }
+
+    pub fn try_edit<R, E, F>(&mut self, f: F) -> Result<R, E>
     where
-        F: FnOnce(&mut &str) -> Result<T, E>,
+        F: FnOnce(&mut &T) -> Result<R, E>,
     {
         self.try_run(|this| this.edit(f))
     }
 
     pub fn range(&self) -> Option<Range<usize>> {
-        let outer = &self.rc[..];
-        let inner = &**self;
-
-
-        let start = outer.as_bytes() as *const [u8] as *const u8 as usize;
-        let len = outer.len();
-        let end = start + len;
-
-        let ptr = self.ptr as *const [u8] as *const u8 as usize;
-
-        if ptr < start || ptr >= end {
-            return None;
+        match self.repr {
+            Repr::Range { start, len } => Some(start..start + len),
+            Repr::Static(_) => None,
         }
+    }
 
-        let ptr_start = ptr-start;
-        let ptr_end = ptr_start + inner.len();
+    pub fn sub(&self, index: impl RangeBounds<usize>) -> Src<T, P> {
+    	let index = range::to_range(index, self.as_elems().len());
+    	let mut s = self.clone();
+    	s.edit(move |s| *s = s.get(index.clone()).unwrap_or_else(|| bad_index(index)));
+    	s.validate();
+    	s
+    }
 
-        Some(ptr_start..ptr_end)
+    pub fn split_at(&self, mid: usize) -> (Self, Self) {
+        self.split_at_checked(mid).unwrap_or_else(|| bad_index(mid..mid))
+    }
 
+    // None if mid is past the end or not on a boundary
+    pub fn split_at_checked(&self, mid: usize) -> Option<(Self, Self)> {
+        let len = self.as_elems().len();
+        self.get(0..mid)?;
+        Some((self.sub(0..mid), self.sub(mid..len)))
     }
 
-    pub fn sub(&self, index: Range<usize>) -> SrcStr {
-    	let mut s = self.clone();
-    	s.edit(move |s| *s = &s[index]);
-    	s
+    pub fn src_sub(&self, index: impl RangeBounds<usize>) -> Src<T, P> {
+        let rc = self.rc.clone().expect("src_sub on a 'static Src");
+        let index = range::to_range(index, rc.elems().len());
+        Self::from_raw_parts(rc, index.clone()).unwrap_or_else(|| bad_index(index))
     }
 
-    pub fn src_sub(&self, index: Range<usize>) -> SrcStr {
-        let mut s = self.clone();
-        let ptr = &s.src()[index] as *const str;
-        s.ptr = ptr;
+    // deliberately leaks the owner (one reference of it) so the slice lives for
+    // the rest of the process; meant for sources that are never dropped anyway
+    pub fn leak(self) -> &'static T {
+        let s = unsafe { &*(&*self as *const T) };
+        core::mem::forget(self);
         s
     }
-}
 
+    // gives self back if it's 'static, since there's no owner to hand out
+    pub fn into_raw_parts(self) -> Result<(P, Range<usize>), Self> {
+        match (self.rc, self.repr) {
+            (Some(rc), Repr::Range { start, len }) => Ok((rc, start..start + len)),
+            (rc, repr) => Err(Src { rc, repr, created: self.created }),
+        }
+    }
 
+    pub fn from_raw_parts(rc: P, index: Range<usize>) -> Option<Self> {
+        if !Self::valid_in(&rc, index.clone()) {
+            return None;
+        }
+        let repr = Repr::Range { start: index.start, len: index.end - index.start };
+        Some(Self { rc: Some(rc), repr, created: Provenance::NONE })
+    }
+}
 
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Src<T, P> {
+    fn valid_in(rc: &P, index: Range<usize>) -> bool {
+        match rc.whole() {
+            Some(src) => src.get(index).is_some(),
+            None => rc.elems().get(index).and_then(T::from_elems).is_some(),
+        }
+    }
 
+    // with the `validate` feature, re-check that the stored range is still a
+    // valid slice of the owner; compiles to nothing otherwise
+    #[inline]
+    fn validate(&self) {
+        #[cfg(feature = "validate")]
+        {
+            if let Repr::Range { start, len } = self.repr {
+                let rc = self.rc.as_ref().expect("Src has a range but no owner");
+                assert!(
+                    Self::valid_in(rc, start..start + len),
+                    "Src range {:?} is not a valid slice of its owner",
+                    start..start + len,
+                );
+            }
+        }
+    }
+}
+
+fn bad_index(index: Range<usize>) -> ! {
+    panic!("range {:?} is out of bounds or not on a boundary", index)
+}
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+    use alloc::vec;
 
     #[test]
     fn index() {
@@ -223,4 +543,207 @@ mod tests {
 
         assert_eq!(a.range(), None);
     }
+
+    #[test]
+    fn range_at_end() {
+        let mut a: SrcStr = "One must still have chaos in oneself to be able to give birth to a dancing star.".into();
+
+        a.edit(|s| *s = &s[s.len()..]);
+
+        assert_eq!(a.range(), Some(80..80));
+        assert_eq!(&a[..], "");
+    }
+
+    #[test]
+    fn tokens() {
+        #[derive(Debug, PartialEq)]
+        enum Token { Word, Space, Dot }
+
+        let a: Src<[Token]> = vec![Token::Word, Token::Space, Token::Word, Token::Dot].into();
+        let b = a.sub(1..3);
+
+        assert_eq!(&b[..], &[Token::Space, Token::Word]);
+        assert_eq!(b.sub(1..2).range(), Some(2..3));
+        assert_ne!(a.sub(0..1), a.sub(2..3));
+
+        let c: Src<[u32]> = vec![1, 2, 3].into();
+        assert_eq!(&c.sub(1..3)[..], &[2, 3]);
+    }
+
+    #[test]
+    fn slice_ref() {
+        let a: SrcStr = "The higher we soar, the smaller we appear to those who cannot fly.".into();
+
+        let word = a[..].split(' ').nth(5).unwrap();
+        assert_eq!(a.slice_ref(word).unwrap().range(), Some(24..31));
+        assert_eq!(a.slice_ref("fly"), None);
+    }
+
+    #[test]
+    fn into_string() {
+        let text = String::from("Become who you are.");
+        let ptr = text.as_ptr();
+        let a: SrcStr = text.into();
+        let b = a.clone();
+
+        assert_eq!(a.sub(7..10).into_string(), "who");
+
+        let copied = a.into_string();
+        assert_ne!(copied.as_ptr(), ptr);
+        let reused = b.into_string();
+        assert_eq!(reused.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn raw_parts() {
+        let a: SrcStr = "Es gibt keine Tatsachen, nur Interpretationen.".into();
+
+        let (rc, range) = a.sub(14..23).into_raw_parts().unwrap();
+        assert_eq!(range, 14..23);
+
+        let b = SrcStr::from_raw_parts(rc.clone(), range).unwrap();
+        assert_eq!(b, a.sub(14..23));
+        assert!(SrcStr::from_raw_parts(rc.clone(), 40..60).is_none());
+        assert!(RcSrcStr::from_static("nur").into_raw_parts().is_err());
+    }
+
+    #[test]
+    fn adopt() {
+        let boxed: Box<str> = "Gratitude is the memory of the heart.".into();
+        let ptr = boxed.as_ptr();
+        let a: SrcStr = boxed.into();
+        assert_eq!(a.as_ptr(), ptr);
+
+        let arc = Arc::new(String::from("We love life, not because we are used to living"));
+        let ptr = arc.as_ptr();
+        let b: SyncSrcStr = arc.into();
+        assert_eq!(b.sub(3..7).as_ptr(), ptr.wrapping_add(3));
+    }
+
+    #[test]
+    fn cow() {
+        let borrowed: SrcStr = Cow::Borrowed("amor fati").into();
+        assert!(borrowed.src().is_none());
+
+        let owned: SrcStr = Cow::<str>::Owned(String::from("amor fati")).into();
+        assert_eq!(owned.sub(5..9).range(), Some(5..9));
+        assert_eq!(owned.as_cow(), borrowed.as_cow());
+    }
+
+    #[test]
+    fn leak() {
+        let a: SrcStr = "Man is something that shall be overcome.".into();
+        let rc = a.src().unwrap().clone();
+
+        let word: &'static str = a.sub(31..39).leak();
+        drop(a);
+        assert_eq!(word, "overcome");
+        assert_eq!(Rc::strong_count(&rc), 2);
+    }
+
+    #[test]
+    fn const_static() {
+        const KEYWORDS: [RcSrcStr; 2] = [SrcStr::from_static("let"), SrcStr::from_static("fn")];
+        static PRELUDE: SyncSrcStr = SrcStr::from_static("fn id(x) = x");
+
+        assert_eq!(&KEYWORDS[1][..], "fn");
+        assert_eq!(&PRELUDE.sub(3..5)[..], "id");
+    }
+
+    #[test]
+    fn niche() {
+        use core::mem::size_of;
+        assert_eq!(size_of::<Option<SrcStr>>(), size_of::<SrcStr>());
+        assert_eq!(size_of::<Option<SyncSrcStr>>(), size_of::<SyncSrcStr>());
+        assert_eq!(size_of::<Option<SmallSrcStr>>(), size_of::<SmallSrcStr>());
+    }
+
+    #[test]
+    fn sync() {
+        let a: SyncSrcStr = "Whoever fights monsters should see to it that in the process he does not become a monster.".into();
+
+        let b = std::thread::spawn(move || a.sub(15..23)).join().unwrap();
+
+        assert_eq!("monsters", &b[..]);
+        assert_eq!(b.range(), Some(15..23));
+    }
+
+    #[test]
+    fn rc_str() {
+        let rc: Rc<str> = "In heaven, all the interesting people are missing.".into();
+        let a: SrcStr<Rc<str>> = rc.clone().into();
+
+        let b = a.sub(19..30);
+
+        assert_eq!("interesting", &b[..]);
+        assert_eq!(b.src().unwrap().as_ptr(), rc.as_ptr());
+    }
+
+    #[test]
+    fn from_static() {
+        let a = SrcStr::<Rc<String>>::from_static("Ich bin kein Mensch, ich bin Dynamit.");
+        let b = a.sub(29..36);
+
+        assert_eq!("Dynamit", &b[..]);
+        assert_eq!(b, a.sub(29..36));
+        assert!(b.src().is_none());
+        assert_eq!(b.range(), None);
+    }
+
+    #[test]
+    fn default() {
+        let a = SrcStr::<Rc<String>>::default();
+
+        assert_eq!("", &a[..]);
+        assert_eq!(a, SrcStr::empty());
+        assert!(a.src().is_none());
+    }
+
+    #[cfg(feature = "validate")]
+    #[test]
+    #[should_panic(expected = "not a valid slice of its owner")]
+    fn validate() {
+        use core::cell::Cell;
+
+        // breaks the SharedOwner contract by shrinking after slices are taken
+        #[derive(Clone)]
+        struct Shrinking(Rc<Cell<usize>>);
+        const TEXT: &str = "Man is a rope, tied between beast and overman.";
+        unsafe impl SharedOwner<str> for Shrinking {
+            fn elems(&self) -> &[u8] {
+                &TEXT.as_bytes()[..self.0.get()]
+            }
+            fn whole(&self) -> Option<&str> {
+                Some(&TEXT[..self.0.get()])
+            }
+        }
+
+        let len = Rc::new(Cell::new(TEXT.len()));
+        let a = SrcStr::from_raw_parts(Shrinking(len.clone()), 9..13).unwrap();
+        assert_eq!("rope", &a[..]);
+
+        len.set(4);
+        let _ = &a[..];
+    }
+
+    #[test]
+    fn split_at() {
+        let a: SrcStr = "Menschliches, Allzumenschliches".into();
+        let (b, c) = a.split_at(12);
+
+        assert_eq!(("Menschliches", ", Allzumenschliches"), (&b[..], &c[..]));
+        assert_eq!((Some(0..12), Some(12..31)), (b.range(), c.range()));
+        assert!(a.split_at_checked(40).is_none());
+        assert!(RcSrcStr::from("Übel").split_at_checked(1).is_none());
+    }
+
+    #[test]
+    fn sub_range_bounds() {
+        let a: SrcStr = "Die fröhliche Wissenschaft".into();
+
+        assert_eq!(Some(0..3), a.sub(..3).range());
+        assert_eq!("Wissenschaft", &a.sub(15..)[..]);
+        assert_eq!("fröhliche", &a.sub(4..=13)[..]);
+        assert_eq!(a.range(), a.sub(..).range());
+    }
 }
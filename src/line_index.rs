@@ -0,0 +1,211 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::SourceBacking;
+
+// where each line starts, for O(log n) line lookups
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+    starts: Vec<usize>, // byte offset of each line, the first always 0
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let newlines = text.bytes().enumerate().filter(|&(_, b)| b == b'\n').map(|(i, _)| i + 1);
+        Self { starts: core::iter::once(0).chain(newlines).collect() }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    // 0-based line holding the byte offset
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    pub fn line_start(&self, line: usize) -> usize {
+        self.starts[line]
+    }
+
+    pub fn starts(&self) -> &[usize] {
+        &self.starts
+    }
+}
+
+const CHAR_STEP: usize = 64;
+
+// the byte offset of every 64th char, so converting between char and byte
+// offsets only scans a short stretch. works on bytes, since some owners
+// aren't a str as a whole
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CharIndex {
+    checkpoints: Vec<usize>,
+    chars: usize,
+}
+
+fn is_char_start(b: u8) -> bool {
+    (b as i8) >= -0x40
+}
+
+impl CharIndex {
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut checkpoints = Vec::new();
+        let mut chars = 0;
+        for (i, &b) in bytes.iter().enumerate() {
+            if is_char_start(b) {
+                if chars % CHAR_STEP == 0 {
+                    checkpoints.push(i);
+                }
+                chars += 1;
+            }
+        }
+        Self { checkpoints, chars }
+    }
+
+    pub fn char_count(&self) -> usize {
+        self.chars
+    }
+
+    // chars before the byte offset, which should be on a boundary
+    pub fn byte_to_char(&self, bytes: &[u8], offset: usize) -> usize {
+        let i = self.checkpoints.partition_point(|&c| c <= offset).saturating_sub(1);
+        let from = self.checkpoints.get(i).copied().unwrap_or(0);
+        i * CHAR_STEP + bytes[from..offset].iter().filter(|&&b| is_char_start(b)).count()
+    }
+
+    // None past the end; the char count itself maps to the end
+    pub fn char_to_byte(&self, bytes: &[u8], char: usize) -> Option<usize> {
+        if char > self.chars {
+            return None;
+        }
+        if char == self.chars {
+            return Some(bytes.len());
+        }
+        let from = self.checkpoints[char / CHAR_STEP];
+        let skip = char % CHAR_STEP;
+        (from..bytes.len()).filter(|&i| is_char_start(bytes[i])).nth(skip)
+    }
+}
+
+// thread-safe where it can be, so Arc<Indexed<_>> sources can be shared
+#[cfg(feature = "std")]
+type OnceCell<T> = std::sync::OnceLock<T>;
+#[cfg(all(not(feature = "std"), target_has_atomic = "ptr"))]
+type OnceCell<T> = RaceCell<T>;
+#[cfg(all(not(feature = "std"), not(target_has_atomic = "ptr")))]
+type OnceCell<T> = core::cell::OnceCell<T>;
+
+// without std there's no way to block, so threads racing to fill the cell
+// each build a value and all but the first to land throw theirs away
+#[cfg(all(not(feature = "std"), target_has_atomic = "ptr"))]
+struct RaceCell<T> {
+    ptr: core::sync::atomic::AtomicPtr<T>,
+    owns: core::marker::PhantomData<alloc::boxed::Box<T>>,
+}
+
+#[cfg(all(not(feature = "std"), target_has_atomic = "ptr"))]
+impl<T> RaceCell<T> {
+    fn new() -> Self {
+        Self { ptr: core::sync::atomic::AtomicPtr::new(core::ptr::null_mut()), owns: core::marker::PhantomData }
+    }
+
+    fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        use core::sync::atomic::Ordering::{AcqRel, Acquire};
+
+        let ptr = self.ptr.load(Acquire);
+        if !ptr.is_null() {
+            // set once and never changed until drop
+            return unsafe { &*ptr };
+        }
+        let new = alloc::boxed::Box::into_raw(alloc::boxed::Box::new(f()));
+        match self.ptr.compare_exchange(core::ptr::null_mut(), new, AcqRel, Acquire) {
+            Ok(_) => unsafe { &*new },
+            Err(won) => {
+                // never shared, since the exchange failed
+                drop(unsafe { alloc::boxed::Box::from_raw(new) });
+                unsafe { &*won }
+            },
+        }
+    }
+}
+
+#[cfg(all(not(feature = "std"), target_has_atomic = "ptr"))]
+impl<T> Drop for RaceCell<T> {
+    fn drop(&mut self) {
+        let ptr = *self.ptr.get_mut();
+        if !ptr.is_null() {
+            drop(unsafe { alloc::boxed::Box::from_raw(ptr) });
+        }
+    }
+}
+
+// a backing that builds its LineIndex (and CharIndex) the first time a slice
+// asks for one
+pub struct Indexed<B = String> {
+    text: B,
+    index: OnceCell<LineIndex>,
+    chars: OnceCell<CharIndex>,
+}
+
+impl<B: SourceBacking> Indexed<B> {
+    pub fn new(text: B) -> Self {
+        Self { text, index: OnceCell::new(), chars: OnceCell::new() }
+    }
+
+    pub fn backing(&self) -> &B {
+        &self.text
+    }
+}
+
+unsafe impl<B: SourceBacking> SourceBacking for Indexed<B> {
+    fn as_str(&self) -> &str {
+        self.text.as_str()
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.text.name()
+    }
+
+    fn meta(&self) -> Option<&dyn core::any::Any> {
+        self.text.meta()
+    }
+
+    fn line_index(&self) -> Option<&LineIndex> {
+        Some(self.index.get_or_init(|| LineIndex::new(self.text.as_str())))
+    }
+
+    fn char_index(&self) -> Option<&CharIndex> {
+        Some(self.chars.get_or_init(|| CharIndex::new(self.text.as_str().as_bytes())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use crate::{Src, SrcStr};
+
+    #[test]
+    fn cached() {
+        let text = "one\ntwo\n\nfour über\n";
+        let rc = Rc::new(Indexed::new(String::from(text)));
+        let a: Src<str, _> = rc.clone().into();
+        let plain: SrcStr = text.into();
+
+        for offset in 0..=text.len() {
+            if text.is_char_boundary(offset) {
+                assert_eq!(plain.line_col_at(offset), a.line_col_at(offset));
+            }
+        }
+        assert_eq!(5, rc.line_index().unwrap().line_count());
+        assert!(core::ptr::eq(rc.line_index().unwrap(), a.sub(4..7).src().unwrap().line_index().unwrap()));
+    }
+
+    #[cfg(target_has_atomic = "ptr")]
+    #[test]
+    fn shareable() {
+        fn send_sync<T: Send + Sync>() {}
+        send_sync::<Src<str, alloc::sync::Arc<Indexed>>>();
+    }
+}
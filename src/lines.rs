@@ -0,0 +1,363 @@
+use core::ops::Deref;
+
+use crate::{bad_index, LineIndex, SharedOwner, Src};
+
+// the whole source and where the slice starts in it. 'static slices are
+// their own source
+fn source<P: SharedOwner<str>>(s: &Src<str, P>) -> (&[u8], usize) {
+    match (s.src(), s.range()) {
+        (Some(rc), Some(range)) => (rc.elems(), range.start),
+        _ => (s.as_bytes(), 0),
+    }
+}
+
+// the owner's cached index, if it describes what `source` returns. a slice
+// edited to a 'static str is its own source, which the index knows nothing of
+fn line_index<P: SharedOwner<str>>(s: &Src<str, P>) -> Option<&LineIndex> {
+    s.range()?;
+    s.src()?.line_index()
+}
+
+// what comes before `offset` (relative to the slice) in the whole source
+fn prefix<P: SharedOwner<str>>(s: &Src<str, P>, offset: usize) -> &[u8] {
+    if offset > s.len() {
+        bad_index(offset..offset);
+    }
+    let (bytes, start) = source(s);
+    &bytes[..start + offset]
+}
+
+// how wide the char starting with this byte is, in the unit columns are
+// counted in. 0 for continuation bytes
+#[derive(Clone, Copy)]
+enum Unit {
+    Char,
+    Utf16,
+}
+
+impl Unit {
+    fn width(self, b: u8) -> usize {
+        match (self, b) {
+            (_, 0x80..=0xbf) => 0,
+            (Unit::Utf16, 0xf0..=0xff) => 2, // surrogate pair
+            _ => 1,
+        }
+    }
+}
+
+fn line_start(bytes: &[u8], index: Option<&LineIndex>, line: usize) -> Option<usize> {
+    match index {
+        Some(index) => index.starts().get(line).copied(),
+        None if line == 0 => Some(0),
+        None => Some(bytes.iter().enumerate().filter(|&(_, &b)| b == b'\n').nth(line - 1)?.0 + 1),
+    }
+}
+
+// byte offset in the whole source of a 1-based line and column. the column
+// can be one past the end of the line, but not inside a char
+fn line_col_to_byte(bytes: &[u8], index: Option<&LineIndex>, line: usize, col: usize, unit: Unit) -> Option<usize> {
+    let line_start = line_start(bytes, index, line.checked_sub(1)?)?;
+    let rest = &bytes[line_start..];
+    let line_len = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+    let (mut i, mut at) = (0, 1);
+    while at < col {
+        if i >= line_len {
+            return None;
+        }
+        at += unit.width(rest[i]);
+        i += 1;
+        while i < line_len && unit.width(rest[i]) == 0 {
+            i += 1;
+        }
+    }
+    if at == col {
+        Some(line_start + i)
+    } else {
+        None
+    }
+}
+
+// 1-based
+fn line_col(before: &[u8], index: Option<&LineIndex>, unit: Unit) -> (usize, usize) {
+    let (line, line_start) = match index {
+        Some(index) => {
+            let line = index.line_of(before.len());
+            (line + 1, index.line_start(line))
+        },
+        None => {
+            let line_start = before.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+            (before[..line_start].iter().filter(|&&b| b == b'\n').count() + 1, line_start)
+        },
+    };
+    (line, before[line_start..].iter().map(|&b| unit.width(b)).sum::<usize>() + 1)
+}
+
+impl<P: SharedOwner<str>> Src<str, P> {
+    pub fn line_col(&self) -> (usize, usize) {
+        self.line_col_at(0)
+    }
+
+    // the offset is into this slice, the line and column are in the whole
+    // source. columns count chars
+    pub fn line_col_at(&self, offset: usize) -> (usize, usize) {
+        line_col(prefix(self, offset), line_index(self), Unit::Char)
+    }
+
+    // columns count utf-16 code units, as lsp positions do (which are 0-based,
+    // so one less than these)
+    pub fn line_col_utf16(&self) -> (usize, usize) {
+        self.line_col_utf16_at(0)
+    }
+
+    pub fn line_col_utf16_at(&self, offset: usize) -> (usize, usize) {
+        line_col(prefix(self, offset), line_index(self), Unit::Utf16)
+    }
+
+    fn to_byte(&self, line: usize, col: usize, unit: Unit) -> Option<usize> {
+        let (bytes, start) = source(self);
+        let offset = line_col_to_byte(bytes, line_index(self), line, col, unit)?;
+        offset.checked_sub(start).filter(|&offset| offset <= self.len())
+    }
+
+    // the reverse of line_col_at: None if the position isn't in the source,
+    // or falls outside this slice
+    pub fn line_col_to_byte(&self, line: usize, col: usize) -> Option<usize> {
+        self.to_byte(line, col, Unit::Char)
+    }
+
+    // also None for a column between the two halves of a surrogate pair
+    pub fn line_col_utf16_to_byte(&self, line: usize, col: usize) -> Option<usize> {
+        self.to_byte(line, col, Unit::Utf16)
+    }
+
+    // 1-based column the offset shows up in on a terminal: tabs move to the
+    // next multiple of tab_width, cjk and other wide chars take two cells,
+    // combining marks none
+    #[cfg(feature = "unicode-width")]
+    pub fn display_column(&self, offset: usize, tab_width: usize) -> usize {
+        let before = prefix(self, offset);
+        let line = &before[before.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1)..];
+        let mut col = 0;
+        for chunk in line.utf8_chunks() {
+            for c in chunk.valid().chars() {
+                col = match c {
+                    '\t' if tab_width > 0 => (col / tab_width + 1) * tab_width,
+                    c => col + unicode_width::UnicodeWidthChar::width(c).unwrap_or(0),
+                };
+            }
+            col += chunk.invalid().len();
+        }
+        col + 1
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Self> + '_ {
+        // same as str::lines: ends at \n or \r\n, which aren't included
+        self.deref().lines().map(move |line| self.subslice(line))
+    }
+
+    pub fn lines_inclusive(&self) -> impl Iterator<Item = Self> + '_ {
+        self.deref().split_inclusive('\n').map(move |line| self.subslice(line))
+    }
+
+    // byte offsets where each line of the whole source starts, the first
+    // always 0. the owner's LineIndex if it has one
+    pub fn line_starts(&self) -> impl Iterator<Item = usize> + '_ {
+        let cached = line_index(self).map(|index| index.starts());
+        let scanned = match cached {
+            Some(_) => None,
+            None => {
+                let bytes = source(self).0;
+                let newlines = bytes.iter().enumerate().filter(|&(_, &b)| b == b'\n').map(|(i, _)| i + 1);
+                Some(core::iter::once(0).chain(newlines))
+            },
+        };
+        cached.into_iter().flatten().copied().chain(scanned.into_iter().flatten())
+    }
+
+    // a range of the whole source (as `source` sees it) as a slice
+    fn in_source(&self, range: core::ops::Range<usize>) -> Self {
+        match self.src() {
+            Some(_) if self.range().is_some() => self.src_sub(range),
+            _ => self.sub(range),
+        }
+    }
+
+    // widened to the start and end of the lines it touches, without the
+    // final line break
+    pub fn containing_lines(&self) -> Self {
+        self.context(0, 0).0
+    }
+
+    // containing_lines plus up to `before` and `after` more lines around,
+    // with where self sits inside the result
+    pub fn context(&self, before: usize, after: usize) -> (Self, core::ops::Range<usize>) {
+        let (bytes, start) = source(self);
+        let end = start + self.len();
+
+        let mut line_start = bytes[..start].iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        for _ in 0..before {
+            if line_start == 0 {
+                break;
+            }
+            line_start = bytes[..line_start - 1].iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        }
+
+        let probe = if end > start && bytes[end - 1] == b'\n' { end - 1 } else { end };
+        let line_end = |from: usize| bytes[from..].iter().position(|&b| b == b'\n').map_or(bytes.len(), |i| from + i);
+        let mut last_end = line_end(probe);
+        for _ in 0..after {
+            if last_end == bytes.len() {
+                break;
+            }
+            last_end = line_end(last_end + 1);
+        }
+        if last_end > end && bytes[last_end - 1] == b'\r' {
+            last_end -= 1;
+        }
+
+        (self.in_source(line_start..last_end), start - line_start..end - line_start)
+    }
+
+    // an empty slice at the position
+    pub fn at_line_col(&self, line: usize, col: usize) -> Option<Self> {
+        let offset = self.line_col_to_byte(line, col)?;
+        Some(self.sub(offset..offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use crate::{Indexed, RcSrcStr, Src, SrcStr};
+
+    #[test]
+    fn line_col() {
+        let a: SrcStr = "Also sprach\nZarathustra:\n  Ich lehre euch den Übermenschen.".into();
+        let b = a.sub(27..60);
+
+        assert_eq!((1, 1), a.line_col());
+        assert_eq!((3, 3), b.line_col());
+        assert_eq!((3, 23), b.line_col_at(21));
+        assert_eq!((3, 35), b.line_col_at(b.len()));
+        assert_eq!((2, 1), a.line_col_at(12));
+        assert_eq!((1, 4), RcSrcStr::from_static("abc").line_col_at(3));
+    }
+
+    #[test]
+    fn edited_to_static() {
+        let mut a: Src<str, _> = Rc::new(Indexed::new(String::from("aaaaaaaaaa\nb"))).into();
+        a.edit(|s| *s = "x\ny");
+
+        assert_eq!((2, 2), a.line_col_at(3));
+        assert_eq!((2, 2), a.line_col_utf16_at(3));
+        assert_eq!(None, a.line_col_to_byte(4, 1));
+        assert_eq!(Some(2), a.line_col_to_byte(2, 1));
+        assert_eq!(vec![0, 2], a.line_starts().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn line_col_to_byte() {
+        let a: SrcStr = "Götzen-
+Dämmerung
+".into();
+
+        assert_eq!(Some(0), a.line_col_to_byte(1, 1));
+        assert_eq!(Some(3), a.line_col_to_byte(1, 3));
+        assert_eq!(Some(8), a.line_col_to_byte(1, 8));
+        assert_eq!(None, a.line_col_to_byte(1, 9));
+        assert_eq!(Some(12), a.line_col_to_byte(2, 3));
+        assert_eq!(Some(20), a.line_col_to_byte(3, 1));
+        assert_eq!(None, a.line_col_to_byte(4, 1));
+        assert_eq!(None, a.line_col_to_byte(0, 1));
+
+        let b = a.sub(9..19);
+        assert_eq!(Some(3), b.line_col_to_byte(2, 3));
+        assert_eq!(None, b.line_col_to_byte(1, 1));
+        assert_eq!(b.at_line_col(2, 3).unwrap().range(), Some(12..12));
+        assert_eq!((2, 3), b.line_col_at(3));
+    }
+
+    #[test]
+    fn lines() {
+        let a: SrcStr = "Jenseits\r\nvon Gut\nund Böse".into();
+        let lines: Vec<_> = a.lines().collect();
+
+        assert_eq!(vec!["Jenseits", "von Gut", "und Böse"], lines.iter().map(|l| &l[..]).collect::<Vec<_>>());
+        assert_eq!(Some(10..17), lines[1].range());
+        assert_eq!(vec!["Jenseits\r\n", "von Gut\n", "und Böse"], a.lines_inclusive().map(|l| l.to_string()).collect::<Vec<_>>());
+
+        let b = RcSrcStr::from_static("one\ntwo");
+        assert_eq!(vec!["one", "two"], b.lines().map(|l| l.to_string()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn containing_lines() {
+        let a: SrcStr = "fn main() {\r\n    let x = ;\n}\n".into();
+
+        assert_eq!("    let x = ;", &a.sub(18..19).containing_lines()[..]);
+        assert_eq!("fn main() {\r\n    let x = ;", &a.sub(3..20).containing_lines()[..]);
+        assert_eq!("    let x = ;", &a.sub(13..27).containing_lines()[..]);
+        assert_eq!("}", &a.sub(27..27).containing_lines()[..]);
+        assert_eq!("", &a.sub(29..29).containing_lines()[..]);
+        // nothing to widen into
+        assert_eq!("\nb", &RcSrcStr::from_static("a\nb\nc").sub(1..3).containing_lines()[..]);
+    }
+
+    #[test]
+    fn context() {
+        let a: SrcStr = "one\ntwo\nthree\nfour\nfive".into();
+        let three = a.sub(8..13);
+
+        let (context, range) = three.context(1, 1);
+        assert_eq!("two\nthree\nfour", &context[..]);
+        assert_eq!("three", &context[range]);
+
+        assert_eq!("one\ntwo\nthree", &three.context(5, 0).0[..]);
+        assert_eq!("three\nfour\nfive", &three.context(0, 5).0[..]);
+        assert_eq!("three", &three.context(0, 0).0[..]);
+    }
+
+    #[test]
+    fn line_starts() {
+        let text = "Morgenröte\nGedanken über\n\ndie moralischen Vorurteile";
+        let a: SrcStr = text.into();
+        let indexed: Src<str, _> = Rc::new(Indexed::new(String::from(text))).into();
+
+        assert_eq!(vec![0, 12, 27, 28], a.sub(13..20).line_starts().collect::<Vec<_>>());
+        assert_eq!(a.line_starts().collect::<Vec<_>>(), indexed.line_starts().collect::<Vec<_>>());
+        assert_eq!(vec![0], RcSrcStr::from_static("").line_starts().collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-width")]
+    fn display_column() {
+        let a: SrcStr = "x\n\tlet 名前 = e\u{301};".into();
+        let b = a.sub(2..a.len());
+
+        assert_eq!(1, b.display_column(0, 4));
+        assert_eq!(5, b.display_column(1, 4));
+        assert_eq!(9, b.display_column(1, 8));
+        assert_eq!(14, b.display_column(b.find('=').unwrap(), 4));
+        assert_eq!(17, b.display_column(b.len() - 1, 4));
+        assert_eq!(3, RcSrcStr::from_static("ab\tc").display_column(2, 0));
+    }
+
+    #[test]
+    fn utf16() {
+        let a: SrcStr = "a😀b\nä😀".into();
+
+        assert_eq!((1, 4), a.line_col_utf16_at(5));
+        assert_eq!((1, 3), a.line_col_at(5));
+        assert_eq!((2, 4), a.line_col_utf16_at(a.len()));
+
+        assert_eq!(Some(5), a.line_col_utf16_to_byte(1, 4));
+        assert_eq!(None, a.line_col_utf16_to_byte(1, 3));
+        assert_eq!(Some(6), a.line_col_utf16_to_byte(1, 5));
+        assert_eq!(Some(9), a.line_col_utf16_to_byte(2, 2));
+        assert_eq!(Some(a.len()), a.line_col_utf16_to_byte(2, 4));
+        assert_eq!(None, a.line_col_utf16_to_byte(2, 5));
+    }
+}
@@ -0,0 +1,69 @@
+use core::any::Any;
+use alloc::string::String;
+
+use crate::{CharIndex, LineIndex, SourceBacking};
+
+// source text with a user value riding along, reachable from every slice
+// through `Src::meta`
+pub struct WithMeta<M, B = String> {
+    meta: M,
+    text: B,
+}
+
+impl<M, B> WithMeta<M, B> {
+    pub fn new(meta: M, text: B) -> Self {
+        Self { meta, text }
+    }
+
+    pub fn get(&self) -> &M {
+        &self.meta
+    }
+
+    pub fn backing(&self) -> &B {
+        &self.text
+    }
+}
+
+unsafe impl<M: Any, B: SourceBacking> SourceBacking for WithMeta<M, B> {
+    fn as_str(&self) -> &str {
+        self.text.as_str()
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.text.name()
+    }
+
+    fn meta(&self) -> Option<&dyn Any> {
+        Some(&self.meta)
+    }
+
+    fn line_index(&self) -> Option<&LineIndex> {
+        self.text.line_index()
+    }
+
+    fn char_index(&self) -> Option<&CharIndex> {
+        self.text.char_index()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use crate::{NamedSrc, Src};
+
+    #[derive(Debug, PartialEq)]
+    struct ModuleId(u32);
+
+    #[test]
+    fn meta() {
+        let rc = Rc::new(WithMeta::new(ModuleId(7), NamedSrc::new("ecce.txt", "How one becomes what one is")));
+        let a: Src<str, _> = rc.into();
+        let b = a.sub(8..15);
+
+        assert_eq!("becomes", &b[..]);
+        assert_eq!(Some(&ModuleId(7)), b.meta::<ModuleId>());
+        assert_eq!(None, b.meta::<u32>());
+        assert_eq!(Some("ecce.txt"), b.name());
+    }
+}
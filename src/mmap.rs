@@ -0,0 +1,71 @@
+use core::convert::TryFrom;
+use core::ops::Deref;
+use core::str::Utf8Error;
+
+use memmap2::Mmap;
+
+use crate::{SharedOwner, SourceBacking, Src};
+
+// a memory map that's been checked to be utf-8
+pub struct MappedStr {
+    map: Mmap,
+}
+
+impl MappedStr {
+    pub fn into_inner(self) -> Mmap {
+        self.map
+    }
+}
+
+impl TryFrom<Mmap> for MappedStr {
+    type Error = Utf8Error;
+    fn try_from(map: Mmap) -> Result<Self, Utf8Error> {
+        core::str::from_utf8(&map)?;
+        Ok(Self { map })
+    }
+}
+
+impl Deref for MappedStr {
+    type Target = str;
+    fn deref(&self) -> &str {
+        unsafe { core::str::from_utf8_unchecked(&self.map) }
+    }
+}
+
+unsafe impl SourceBacking for MappedStr {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
+
+impl<P: SharedOwner<str> + From<MappedStr>> Src<str, P> {
+    pub fn from_mmap(map: Mmap) -> Result<Self, Utf8Error> {
+        Ok(Self::whole(P::from(MappedStr::try_from(map)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::format;
+    use std::fs::File;
+    use std::io::Write;
+    use std::rc::Rc;
+
+    #[test]
+    fn mapped() {
+        let path = std::env::temp_dir().join(format!("srcstr-mmap-{}", std::process::id()));
+        File::create(&path).unwrap().write_all(b"He who has a why to live can bear almost any how.").unwrap();
+
+        let map = unsafe { Mmap::map(&File::open(&path).unwrap()).unwrap() };
+        let a = Src::<str, Rc<MappedStr>>::from_mmap(map).unwrap();
+        let why = a.sub(13..16);
+
+        assert_eq!("why", &why[..]);
+        assert_eq!(why.range(), Some(13..16));
+        assert_eq!(why.as_ptr(), a.src().unwrap().map.as_ptr().wrapping_add(13));
+
+        drop((a, why));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,57 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+
+use crate::{SourceBacking, Src};
+
+// source text along with the file name (or other label) it was loaded from
+pub struct NamedSrc {
+    name: String,
+    text: String,
+}
+
+impl NamedSrc {
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self { name: name.into(), text: text.into() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+unsafe impl SourceBacking for NamedSrc {
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+}
+
+impl Src<str, Rc<NamedSrc>> {
+    pub fn named(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Rc::new(NamedSrc::new(name, text)).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::format;
+    use crate::RcSrcStr;
+
+    #[test]
+    fn name() {
+        let a = Src::named("zarathustra.txt", "God is dead. God remains dead. And we have killed him.");
+        let b = a.sub(13..29);
+
+        assert_eq!(Some("zarathustra.txt"), b.name());
+        assert_eq!("zarathustra.txt:13..29: God remains dead", format!("{:?}", b));
+        assert_eq!(None, RcSrcStr::from("unnamed").name());
+    }
+}
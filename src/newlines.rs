@@ -0,0 +1,81 @@
+use core::ops::Range;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SourceBacking, Src};
+
+pub type NormalizedSrcStr = Src<str, Rc<Normalized>>;
+
+// text with CRLF and lone CR turned into LF, able to map offsets back to the
+// text as it was
+pub struct Normalized {
+    text: String,
+    crlf: Vec<usize>, // positions of the LFs that replaced a CRLF
+}
+
+impl Normalized {
+    pub fn new(original: &str) -> Self {
+        let mut text = String::with_capacity(original.len());
+        let mut crlf = Vec::new();
+        let mut rest = original;
+        while let Some(i) = rest.find('\r') {
+            text.push_str(&rest[..i]);
+            if rest[i + 1..].starts_with('\n') {
+                crlf.push(text.len());
+                rest = &rest[i + 2..];
+            } else {
+                rest = &rest[i + 1..];
+            }
+            text.push('\n');
+        }
+        text.push_str(rest);
+        Self { text, crlf }
+    }
+
+    pub fn original_offset(&self, offset: usize) -> usize {
+        offset + self.crlf.partition_point(|&lf| lf < offset)
+    }
+}
+
+unsafe impl SourceBacking for Normalized {
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Src<str, Rc<Normalized>> {
+    pub fn normalized(original: &str) -> Self {
+        Rc::new(Normalized::new(original)).into()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn load_normalized(path: &std::path::Path) -> std::io::Result<Self> {
+        Ok(Self::normalized(&std::fs::read_to_string(path)?))
+    }
+
+    // where this slice was in the text before normalizing
+    pub fn original_range(&self) -> Option<Range<usize>> {
+        let (normalized, range) = (self.src()?, self.range()?);
+        Some(normalized.original_offset(range.start)..normalized.original_offset(range.end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn original_range() {
+        let original = "one\r\ntwo\rthree\r\nfour";
+        let a = NormalizedSrcStr::normalized(original);
+        assert_eq!("one\ntwo\nthree\nfour", &a[..]);
+
+        let three = a.sub(8..13);
+        assert_eq!("three", &three[..]);
+        assert_eq!(Some(9..14), three.original_range());
+        assert_eq!("three", &original[three.original_range().unwrap()]);
+        assert_eq!(Some(16..20), a.sub(14..18).original_range());
+        assert_eq!(Some(3..5), a.sub(3..4).original_range());
+    }
+}
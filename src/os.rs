@@ -0,0 +1,198 @@
+use core::fmt;
+use core::ops::Range;
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+#[cfg(target_has_atomic = "ptr")]
+use std::sync::Arc;
+
+use crate::{SharedOwner, Slice, Src};
+
+pub type SrcOsStr<P = Rc<OsString>> = Src<OsStr, P>;
+pub type SrcPath<P = Rc<PathBuf>> = Src<Path, P>;
+
+// encoded bytes can only be split right before or after valid utf-8
+fn is_boundary(bytes: &[u8], i: usize) -> bool {
+    if i == 0 || i == bytes.len() {
+        return true;
+    }
+    if i > bytes.len() {
+        return false;
+    }
+    let before = (1..=i.min(4)).any(|k| core::str::from_utf8(&bytes[i - k..i]).is_ok());
+    let after = (1..=(bytes.len() - i).min(4)).any(|k| core::str::from_utf8(&bytes[i..i + k]).is_ok());
+    before || after
+}
+
+unsafe impl Slice for OsStr {
+    type Elem = u8;
+    type Owned = OsString;
+
+    fn as_elems(&self) -> &[u8] {
+        self.as_encoded_bytes()
+    }
+
+    fn empty() -> &'static OsStr {
+        OsStr::new("")
+    }
+
+    fn get(&self, index: Range<usize>) -> Option<&OsStr> {
+        let bytes = self.as_encoded_bytes();
+        if index.start > index.end || !is_boundary(bytes, index.start) || !is_boundary(bytes, index.end) {
+            return None;
+        }
+        Some(unsafe { OsStr::from_encoded_bytes_unchecked(&bytes[index]) })
+    }
+
+    // anything else has to come from slicing an existing OsStr
+    fn from_elems(elems: &[u8]) -> Option<&OsStr> {
+        core::str::from_utf8(elems).ok().map(OsStr::new)
+    }
+
+    unsafe fn from_elems_unchecked(elems: &[u8]) -> &OsStr {
+        OsStr::from_encoded_bytes_unchecked(elems)
+    }
+}
+
+unsafe impl Slice for Path {
+    type Elem = u8;
+    type Owned = PathBuf;
+
+    fn as_elems(&self) -> &[u8] {
+        self.as_os_str().as_encoded_bytes()
+    }
+
+    fn empty() -> &'static Path {
+        Path::new("")
+    }
+
+    fn get(&self, index: Range<usize>) -> Option<&Path> {
+        self.as_os_str().get(index).map(Path::new)
+    }
+
+    fn from_elems(elems: &[u8]) -> Option<&Path> {
+        OsStr::from_elems(elems).map(Path::new)
+    }
+
+    unsafe fn from_elems_unchecked(elems: &[u8]) -> &Path {
+        Path::new(OsStr::from_elems_unchecked(elems))
+    }
+}
+
+macro_rules! os_owner {
+    ($slice:ty: $($owner:ty),*) => {$(
+        unsafe impl SharedOwner<$slice> for $owner {
+            fn elems(&self) -> &[u8] {
+                self.as_elems()
+            }
+
+            fn whole(&self) -> Option<&$slice> {
+                Some(self)
+            }
+        }
+
+        impl From<$owner> for Src<$slice, $owner> {
+            fn from(rc: $owner) -> Self {
+                Self::whole(rc)
+            }
+        }
+    )*};
+}
+
+os_owner!(OsStr: Rc<OsString>, Rc<OsStr>);
+os_owner!(Path: Rc<PathBuf>, Rc<Path>);
+#[cfg(target_has_atomic = "ptr")]
+os_owner!(OsStr: Arc<OsString>, Arc<OsStr>);
+#[cfg(target_has_atomic = "ptr")]
+os_owner!(Path: Arc<PathBuf>, Arc<Path>);
+
+impl<P: SharedOwner<OsStr> + From<OsString>> From<OsString> for Src<OsStr, P> {
+    fn from(s: OsString) -> Self {
+        Self::whole(P::from(s))
+    }
+}
+
+impl<P: SharedOwner<Path> + From<PathBuf>> From<PathBuf> for Src<Path, P> {
+    fn from(path: PathBuf) -> Self {
+        Self::whole(P::from(path))
+    }
+}
+
+impl<P: SharedOwner<OsStr>> fmt::Debug for Src<OsStr, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<P: SharedOwner<Path>> fmt::Debug for Src<Path, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+fn is_separator(b: u8) -> bool {
+    b.is_ascii() && std::path::is_separator(b as char)
+}
+
+// where the next `name` segment starts, skipping separators and the `.`
+// segments that components() drops along the way
+fn segment_at(bytes: &[u8], mut at: usize, name: &[u8]) -> usize {
+    loop {
+        while at < bytes.len() && is_separator(bytes[at]) {
+            at += 1;
+        }
+        let end = at + bytes[at..].iter().position(|&b| is_separator(b)).unwrap_or(bytes.len() - at);
+        if &bytes[at..end] == name || end == bytes.len() {
+            return at;
+        }
+        at = end;
+    }
+}
+
+impl<P: SharedOwner<Path>> Src<Path, P> {
+    // every component, separators and dots included, as a slice of the path
+    pub fn components(&self) -> impl Iterator<Item = Src<Path, P>> + '_ {
+        let bytes = self.as_os_str().as_encoded_bytes();
+        let mut at = 0;
+        (**self).components().map(move |c| {
+            let start = match c {
+                Component::Prefix(_) | Component::Normal(_) => c.as_os_str().as_encoded_bytes().as_ptr() as usize - bytes.as_ptr() as usize,
+                Component::RootDir => at + bytes[at..].iter().position(|&b| is_separator(b)).unwrap_or(0),
+                Component::CurDir | Component::ParentDir => segment_at(bytes, at, c.as_os_str().as_encoded_bytes()),
+            };
+            let len = match c {
+                Component::RootDir => 1,
+                c => c.as_os_str().len(),
+            };
+            at = start + len;
+            self.sub(start..at)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn components() {
+        let a: SrcPath = PathBuf::from("/usr/lib/zarathustra/../bin").into();
+
+        let names: std::vec::Vec<_> = a.components().map(|c| c.range()).collect();
+        assert_eq!(names, [Some(0..1), Some(1..4), Some(5..8), Some(9..20), Some(21..23), Some(24..27)]);
+
+        let b: SrcPath = PathBuf::from("./a/./../b").into();
+        let parts: std::vec::Vec<_> = b.components().map(|c| (c.range().unwrap(), c.to_path_buf())).collect();
+        let named = |range, name| (range, PathBuf::from(name));
+        assert_eq!(parts, [named(0..1, "."), named(2..3, "a"), named(6..8, ".."), named(9..10, "b")]);
+        assert_eq!(Some(2), b.components().nth(2).unwrap().offset_in(&b.sub(4..10)));
+    }
+
+    #[test]
+    fn sub() {
+        let a: SrcOsStr = OsString::from("--übermensch=yes").into();
+
+        assert_eq!(&*a.sub(2..13), OsStr::new("übermensch"));
+        assert!(OsStr::get(&a, 3..5).is_none());
+    }
+}
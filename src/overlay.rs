@@ -0,0 +1,122 @@
+use core::ops::Range;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, SrcStr};
+
+// builds a preprocessed buffer, remembering where each region was copied from
+pub struct OverlayBuilder<P: SharedOwner<str> = Rc<String>> {
+    text: String,
+    regions: Vec<(Range<usize>, Option<SrcStr<P>>)>, // None for generated text
+}
+
+// a concatenated buffer whose offsets resolve back to the original sources,
+// like #line directives
+pub struct Overlay<P: SharedOwner<str> = Rc<String>> {
+    text: SrcStr<P>,
+    regions: Vec<(Range<usize>, Option<SrcStr<P>>)>,
+}
+
+impl<P: SharedOwner<str>> Default for OverlayBuilder<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: SharedOwner<str>> OverlayBuilder<P> {
+    pub fn new() -> Self {
+        Self { text: String::new(), regions: Vec::new() }
+    }
+
+    pub fn push_src(&mut self, src: &SrcStr<P>) -> &mut Self {
+        self.push(src, Some(src.clone()))
+    }
+
+    // text that has no origin, e.g. a macro's own expansion
+    pub fn push_str(&mut self, text: &str) -> &mut Self {
+        self.push(text, None)
+    }
+
+    fn push(&mut self, text: &str, origin: Option<SrcStr<P>>) -> &mut Self {
+        let start = self.text.len();
+        self.text.push_str(text);
+        self.regions.push((start..self.text.len(), origin));
+        self
+    }
+
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn finish(self) -> Overlay<P>
+    where
+        P: From<String>,
+    {
+        Overlay { text: self.text.into(), regions: self.regions }
+    }
+}
+
+impl<P: SharedOwner<str>> Overlay<P> {
+    pub fn text(&self) -> &SrcStr<P> {
+        &self.text
+    }
+
+    // the region a range starts in. an empty range at a region's end stays
+    // with that region
+    fn region_at(&self, range: &Range<usize>) -> Option<&(Range<usize>, Option<SrcStr<P>>)> {
+        let at = range.start;
+        let i = self.regions.partition_point(|(r, _)| r.end < at || (r.end == at && !range.is_empty()));
+        self.regions.get(i).filter(|(r, _)| r.start <= at)
+    }
+
+    // an empty span at the original position of `offset`
+    pub fn resolve(&self, offset: usize) -> Option<SrcStr<P>> {
+        self.resolve_range(offset..offset)
+    }
+
+    // the range has to stay within one copied region
+    pub fn resolve_range(&self, range: Range<usize>) -> Option<SrcStr<P>> {
+        let (region, origin) = self.region_at(&range)?;
+        let origin = origin.as_ref()?;
+        if range.end > region.end {
+            return None;
+        }
+        let local = range.start - region.start..range.end - region.start;
+        origin.get(local.clone())?;
+        Some(origin.sub(local))
+    }
+
+    pub fn resolve_span(&self, span: &SrcStr<P>) -> Option<SrcStr<P>> {
+        let range = span.range()?;
+        let outer = self.text.range()?;
+        if span.src_ptr() != self.text.src_ptr() || range.end > outer.end {
+            return None;
+        }
+        self.resolve_range(range.start - outer.start..range.end - outer.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve() {
+        let header: SrcStr = "int limit;\n".into();
+        let main: SrcStr = "#include \"h\"\nint main;\n".into();
+
+        let mut builder = OverlayBuilder::new();
+        builder.push_src(&header).push_str("// generated\n").push_src(&main.sub(13..23));
+        let overlay = builder.finish();
+        assert_eq!("int limit;\n// generated\nint main;\n", &overlay.text()[..]);
+
+        let limit = overlay.resolve_range(4..9).unwrap();
+        assert_eq!("limit", &limit[..]);
+        assert_eq!(limit, header.sub(4..9));
+        assert_eq!(overlay.resolve(28).unwrap().range(), Some(17..17));
+        assert!(overlay.resolve(15).is_none());
+        assert!(overlay.resolve_range(8..30).is_none());
+        assert_eq!(overlay.resolve_range(24..27), Some(main.sub(13..16)));
+
+        let main_span = overlay.text().sub(28..32);
+        assert_eq!(Some(main.sub(17..21)), overlay.resolve_span(&main_span));
+    }
+}
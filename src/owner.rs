@@ -0,0 +1,263 @@
+use core::any::Any;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+
+use crate::{CharIndex, LineIndex, Slice, Src};
+
+/// # Safety
+///
+/// `elems` must return the same elements, at the same address, for as long
+/// as any clone of the owner is alive, and `whole` must be `Some` only if all
+/// of those elements make up a valid `T`.
+pub unsafe trait SharedOwner<T: ?Sized + Slice = str>: Clone {
+    fn elems(&self) -> &[T::Elem];
+
+    // owners that are known to be a valid T throughout get cheaper slicing
+    fn whole(&self) -> Option<&T> {
+        None
+    }
+
+    // a file name or other label for where the source came from
+    fn name(&self) -> Option<&str> {
+        None
+    }
+
+    // whatever the user attached to the source, see `WithMeta`
+    fn meta(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    // a cached index for line lookups, see `Indexed`
+    fn line_index(&self) -> Option<&LineIndex> {
+        None
+    }
+
+    fn char_index(&self) -> Option<&CharIndex> {
+        None
+    }
+
+    // tells owners apart for as long as both are alive. the default, the text's
+    // address, is shared by every empty owner that doesn't allocate
+    fn identity(&self) -> usize {
+        self.elems().as_ptr() as usize
+    }
+}
+
+/// Text storage that can sit behind an `Rc`, an `Arc`, or a `&'static`, and
+/// so back a `SrcStr` (arenas, interners, memory maps, ...).
+///
+/// # Safety
+///
+/// `as_str` must return the same text, at the same address, for as long as
+/// the backing is alive.
+pub unsafe trait SourceBacking {
+    fn as_str(&self) -> &str;
+
+    fn name(&self) -> Option<&str> {
+        None
+    }
+
+    fn meta(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    fn line_index(&self) -> Option<&LineIndex> {
+        None
+    }
+
+    fn char_index(&self) -> Option<&CharIndex> {
+        None
+    }
+}
+
+unsafe impl SourceBacking for String {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
+
+unsafe impl SourceBacking for str {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
+
+macro_rules! backed_owner {
+    ($($rc:ident),*) => {$(
+        unsafe impl<B: ?Sized + SourceBacking> SharedOwner<str> for $rc<B> {
+            fn elems(&self) -> &[u8] {
+                (**self).as_str().as_bytes()
+            }
+
+            fn whole(&self) -> Option<&str> {
+                Some((**self).as_str())
+            }
+
+            fn name(&self) -> Option<&str> {
+                (**self).name()
+            }
+
+            fn meta(&self) -> Option<&dyn Any> {
+                (**self).meta()
+            }
+
+            fn line_index(&self) -> Option<&LineIndex> {
+                (**self).line_index()
+            }
+
+            fn char_index(&self) -> Option<&CharIndex> {
+                (**self).char_index()
+            }
+
+            fn identity(&self) -> usize {
+                $rc::as_ptr(self) as *const () as usize
+            }
+        }
+
+        unsafe impl<B: ?Sized + SourceBacking> SharedOwner<[u8]> for $rc<B> {
+            fn elems(&self) -> &[u8] {
+                (**self).as_str().as_bytes()
+            }
+
+            fn whole(&self) -> Option<&[u8]> {
+                Some((**self).as_str().as_bytes())
+            }
+
+            fn name(&self) -> Option<&str> {
+                (**self).name()
+            }
+
+            fn meta(&self) -> Option<&dyn Any> {
+                (**self).meta()
+            }
+
+            fn line_index(&self) -> Option<&LineIndex> {
+                (**self).line_index()
+            }
+
+            fn char_index(&self) -> Option<&CharIndex> {
+                (**self).char_index()
+            }
+
+            fn identity(&self) -> usize {
+                $rc::as_ptr(self) as *const () as usize
+            }
+        }
+
+        impl<B: ?Sized + SourceBacking> From<$rc<B>> for Src<str, $rc<B>> {
+            fn from(rc: $rc<B>) -> Self {
+                Self::whole(rc)
+            }
+        }
+    )*};
+}
+
+// for leaked or arena storage that's never freed
+unsafe impl<B: ?Sized + SourceBacking> SharedOwner<str> for &'static B {
+    fn elems(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
+
+    fn whole(&self) -> Option<&str> {
+        Some(self.as_str())
+    }
+
+    fn name(&self) -> Option<&str> {
+        (**self).name()
+    }
+
+    fn meta(&self) -> Option<&dyn Any> {
+        (**self).meta()
+    }
+
+    fn line_index(&self) -> Option<&LineIndex> {
+        (**self).line_index()
+    }
+
+    fn char_index(&self) -> Option<&CharIndex> {
+        (**self).char_index()
+    }
+
+    fn identity(&self) -> usize {
+        *self as *const B as *const () as usize
+    }
+}
+
+macro_rules! elem_owner {
+    ($($owner:ident<$elems:ty>),*) => {$(
+        unsafe impl<E: 'static> SharedOwner<[E]> for $owner<$elems> {
+            fn elems(&self) -> &[E] {
+                self
+            }
+
+            fn whole(&self) -> Option<&[E]> {
+                Some(self)
+            }
+
+            fn identity(&self) -> usize {
+                $owner::as_ptr(self) as *const () as usize
+            }
+        }
+
+        impl<E: 'static> From<$owner<$elems>> for Src<[E], $owner<$elems>> {
+            fn from(rc: $owner<$elems>) -> Self {
+                Self::whole(rc)
+            }
+        }
+    )*};
+}
+
+// byte owners can back text too, with each slice checked for utf-8
+macro_rules! bytes_owner {
+    ($($owner:ident<$elems:ty>),*) => {$(
+        unsafe impl SharedOwner<str> for $owner<$elems> {
+            fn elems(&self) -> &[u8] {
+                self
+            }
+
+            fn identity(&self) -> usize {
+                $owner::as_ptr(self) as *const () as usize
+            }
+        }
+    )*};
+}
+
+backed_owner!(Rc);
+#[cfg(target_has_atomic = "ptr")]
+backed_owner!(Arc);
+
+elem_owner!(Rc<Vec<E>>, Rc<[E]>);
+#[cfg(target_has_atomic = "ptr")]
+elem_owner!(Arc<Vec<E>>, Arc<[E]>);
+
+bytes_owner!(Rc<Vec<u8>>, Rc<[u8]>);
+#[cfg(target_has_atomic = "ptr")]
+bytes_owner!(Arc<Vec<u8>>, Arc<[u8]>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::boxed::Box;
+
+    // stands in for an interner that hands out stable, never-freed chunks
+    struct Interned(&'static str);
+
+    unsafe impl SourceBacking for Interned {
+        fn as_str(&self) -> &str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn custom_backing() {
+        let chunk: &'static Interned = Box::leak(Box::new(Interned("the will to power")));
+        let a = Src::<str, &'static Interned>::from_raw_parts(chunk, 0..17).unwrap();
+        assert_eq!("power", &a.sub(12..17)[..]);
+
+        let b: Src<str, Rc<Interned>> = Rc::new(Interned("the will to power")).into();
+        assert_eq!(b.sub(4..8).range(), Some(4..8));
+    }
+}
@@ -0,0 +1,126 @@
+use alloc::string::String;
+
+// what the spanned search and split methods accept: a char, a string, a set of
+// chars or a char predicate, like str's (unstable) Pattern. sealed, so the
+// list can grow without breaking anyone
+pub trait SrcPattern: sealed::Search {}
+
+pub(crate) mod sealed {
+    pub trait Search: Sized {
+        fn find(self, s: &str) -> Option<usize>;
+        fn split<'a>(self, s: &'a str) -> impl Iterator<Item = &'a str> + 'a
+        where
+            Self: 'a;
+        fn splitn<'a>(self, s: &'a str, n: usize) -> impl Iterator<Item = &'a str> + 'a
+        where
+            Self: 'a;
+        fn rsplit<'a>(self, s: &'a str) -> impl Iterator<Item = &'a str> + 'a
+        where
+            Self: 'a;
+        fn rsplitn<'a>(self, s: &'a str, n: usize) -> impl Iterator<Item = &'a str> + 'a
+        where
+            Self: 'a;
+        fn split_terminator<'a>(self, s: &'a str) -> impl Iterator<Item = &'a str> + 'a
+        where
+            Self: 'a;
+        fn split_once(self, s: &str) -> Option<(&str, &str)>;
+        fn rsplit_once(self, s: &str) -> Option<(&str, &str)>;
+        fn strip_prefix(self, s: &str) -> Option<&str>;
+        fn strip_suffix(self, s: &str) -> Option<&str>;
+        fn match_indices<'a>(self, s: &'a str) -> impl Iterator<Item = (usize, &'a str)> + 'a
+        where
+            Self: 'a;
+        fn rmatch_indices<'a>(self, s: &'a str) -> impl Iterator<Item = (usize, &'a str)> + 'a
+        where
+            Self: 'a;
+    }
+}
+
+// each just hands the pattern on to str's method of the same name
+macro_rules! patterns {
+    ($([$($generics:tt)*] $ty:ty),*) => {$(
+        impl<$($generics)*> SrcPattern for $ty {}
+
+        impl<$($generics)*> sealed::Search for $ty {
+            fn find(self, s: &str) -> Option<usize> {
+                s.find(self)
+            }
+
+            fn split<'a>(self, s: &'a str) -> impl Iterator<Item = &'a str> + 'a
+            where
+                Self: 'a,
+            {
+                s.split(self)
+            }
+
+            fn splitn<'a>(self, s: &'a str, n: usize) -> impl Iterator<Item = &'a str> + 'a
+            where
+                Self: 'a,
+            {
+                s.splitn(n, self)
+            }
+
+            fn rsplit<'a>(self, s: &'a str) -> impl Iterator<Item = &'a str> + 'a
+            where
+                Self: 'a,
+            {
+                s.rsplit(self)
+            }
+
+            fn rsplitn<'a>(self, s: &'a str, n: usize) -> impl Iterator<Item = &'a str> + 'a
+            where
+                Self: 'a,
+            {
+                s.rsplitn(n, self)
+            }
+
+            fn split_terminator<'a>(self, s: &'a str) -> impl Iterator<Item = &'a str> + 'a
+            where
+                Self: 'a,
+            {
+                s.split_terminator(self)
+            }
+
+            fn split_once(self, s: &str) -> Option<(&str, &str)> {
+                s.split_once(self)
+            }
+
+            fn rsplit_once(self, s: &str) -> Option<(&str, &str)> {
+                s.rsplit_once(self)
+            }
+
+            fn strip_prefix(self, s: &str) -> Option<&str> {
+                s.strip_prefix(self)
+            }
+
+            fn strip_suffix(self, s: &str) -> Option<&str> {
+                s.strip_suffix(self)
+            }
+
+            fn match_indices<'a>(self, s: &'a str) -> impl Iterator<Item = (usize, &'a str)> + 'a
+            where
+                Self: 'a,
+            {
+                s.match_indices(self)
+            }
+
+            fn rmatch_indices<'a>(self, s: &'a str) -> impl Iterator<Item = (usize, &'a str)> + 'a
+            where
+                Self: 'a,
+            {
+                s.rmatch_indices(self)
+            }
+        }
+    )*};
+}
+
+patterns!(
+    [] char,
+    ['b] &'b str,
+    ['b] &'b String,
+    ['b, 'c] &'b &'c str,
+    ['b] &'b [char],
+    [const N: usize] [char; N],
+    ['b, const N: usize] &'b [char; N],
+    [F: FnMut(char) -> bool] F
+);
@@ -0,0 +1,62 @@
+#[cfg(feature = "provenance")]
+use core::panic::Location;
+
+#[cfg(feature = "provenance")]
+use crate::{Slice, SharedOwner, Src};
+
+// where a source was created. takes no space unless the `provenance` feature is on
+#[derive(Clone, Copy)]
+pub(crate) struct Provenance {
+    #[cfg(feature = "provenance")]
+    at: Option<&'static Location<'static>>,
+}
+
+impl Provenance {
+    pub(crate) const NONE: Self = Self {
+        #[cfg(feature = "provenance")]
+        at: None,
+    };
+
+    #[track_caller]
+    pub(crate) fn here() -> Self {
+        Self {
+            #[cfg(feature = "provenance")]
+            at: Some(Location::caller()),
+        }
+    }
+}
+
+#[cfg(feature = "provenance")]
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Src<T, P> {
+    // the code that turned a String or &str into this source, if that's how it was made
+    pub fn created_at(&self) -> Option<&'static Location<'static>> {
+        self.created.at
+    }
+}
+
+#[cfg(all(test, feature = "provenance"))]
+mod tests {
+    use alloc::boxed::Box;
+
+    use crate::{RcSrcStr, SrcStr};
+
+    #[test]
+    fn created_at() {
+        let (a, line): (SrcStr, _) = ("Dawn".into(), line!());
+        let at = a.sub(1..3).created_at().unwrap();
+
+        assert_eq!((file!(), line), (at.file(), at.line()));
+        assert!(RcSrcStr::from_static("dusk").created_at().is_none());
+    }
+
+    #[test]
+    fn created_at_through_constructors() {
+        let (a, line) = (RcSrcStr::from_utf8(b"Daybreak".to_vec()).unwrap(), line!());
+        let at = a.created_at().unwrap();
+        assert_eq!((file!(), line), (at.file(), at.line()));
+
+        let (b, line) = (RcSrcStr::from(Box::<str>::from("Twilight")), line!());
+        let at = b.created_at().unwrap();
+        assert_eq!((file!(), line), (at.file(), at.line()));
+    }
+}
@@ -0,0 +1,182 @@
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::{Bound, Range, RangeBounds, RangeFull};
+
+use crate::{SharedOwner, Slice, Src};
+
+// why a range couldn't be sliced out of a source
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RangeError {
+    OutOfBounds { range: Range<usize>, len: usize },
+    NotCharBoundary { index: usize },
+    // src_sub on a 'static slice, which has no source to index into
+    NoSource,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::OutOfBounds { range, len } => {
+                write!(f, "range {:?} is out of bounds for length {}", range, len)
+            },
+            RangeError::NotCharBoundary { index } => write!(f, "index {} is not a char boundary", index),
+            RangeError::NoSource => f.write_str("a 'static slice has no source to index into"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RangeError {}
+
+// resolves open ends against len. doesn't check anything, so an inverted or
+// too-long range comes out as-is (or saturated, for ..=usize::MAX)
+pub(crate) fn to_range(index: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+    let start = match index.start_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => i.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match index.end_bound() {
+        Bound::Included(&i) => i.saturating_add(1),
+        Bound::Excluded(&i) => i,
+        Bound::Unbounded => len,
+    };
+    start..end
+}
+
+fn is_char_boundary(bytes: &[u8], index: usize) -> bool {
+    // same rule as str::is_char_boundary, but works on owners that are raw bytes
+    bytes.get(index).map_or(index == bytes.len(), |&b| (b as i8) >= -0x40)
+}
+
+impl<P: SharedOwner<str>> Src<str, P> {
+    pub fn new(rc: P, index: Range<usize>) -> Result<Self, RangeError> {
+        let bytes = rc.elems();
+        if index.start > index.end || index.end > bytes.len() {
+            return Err(RangeError::OutOfBounds { range: index, len: bytes.len() });
+        }
+        for &i in &[index.start, index.end] {
+            if !is_char_boundary(bytes, i) {
+                return Err(RangeError::NotCharBoundary { index: i });
+            }
+        }
+        // can still fail for byte owners that aren't utf-8 inside the range
+        let start = index.start;
+        Self::from_raw_parts(rc, index).ok_or(RangeError::NotCharBoundary { index: start })
+    }
+}
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Src<T, P> {
+    // sub, but None rather than a panic
+    pub fn get(&self, index: Range<usize>) -> Option<Self> {
+        self.try_sub(index).ok()
+    }
+
+    // sub, but saying what was wrong with the range
+    pub fn try_sub(&self, index: Range<usize>) -> Result<Self, RangeError> {
+        let len = self.as_elems().len();
+        if index.start > index.end || index.end > len {
+            return Err(RangeError::OutOfBounds { range: index, len });
+        }
+        for &i in &[index.start, index.end] {
+            if Slice::get(&**self, i..i).is_none() {
+                return Err(RangeError::NotCharBoundary { index: i });
+            }
+        }
+        Ok(self.sub(index))
+    }
+
+    // src_sub, but saying what was wrong with the range
+    pub fn try_src_sub(&self, index: impl RangeBounds<usize>) -> Result<Self, RangeError> {
+        let rc = self.src().ok_or(RangeError::NoSource)?;
+        let len = rc.elems().len();
+        let index = to_range(index, len);
+        if index.start > index.end || index.end > len {
+            return Err(RangeError::OutOfBounds { range: index, len });
+        }
+        for &i in &[index.start, index.end] {
+            if !Self::valid_in(rc, i..i) {
+                return Err(RangeError::NotCharBoundary { index: i });
+            }
+        }
+        // byte owners can still have bad utf-8 inside the range
+        let start = index.start;
+        Self::from_raw_parts(rc.clone(), index).ok_or(RangeError::NotCharBoundary { index: start })
+    }
+}
+
+impl<P: SharedOwner<str>> TryFrom<(P, Range<usize>)> for Src<str, P> {
+    type Error = RangeError;
+
+    fn try_from((rc, index): (P, Range<usize>)) -> Result<Self, RangeError> {
+        Self::new(rc, index)
+    }
+}
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> From<(P, RangeFull)> for Src<T, P>
+where
+    Self: From<P>,
+{
+    fn from((rc, _): (P, RangeFull)) -> Self {
+        rc.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use std::format;
+    use crate::{RcSrcStr, SrcStr};
+
+    #[test]
+    fn new() {
+        let rc = Rc::new(String::from("Wer mit Ungeheuern kämpft, mag zusehn, dass er nicht dabei zum Ungeheuer wird."));
+
+        let a = SrcStr::new(rc.clone(), 8..18).unwrap();
+        assert_eq!("Ungeheuern", &a[..]);
+        assert_eq!(a.src().unwrap().as_ptr(), rc.as_ptr());
+
+        assert_eq!(SrcStr::new(rc.clone(), 0..200).unwrap_err(), RangeError::OutOfBounds { range: 0..200, len: rc.len() });
+        assert_eq!(SrcStr::new(rc.clone(), 19..21).unwrap_err(), RangeError::NotCharBoundary { index: 21 });
+    }
+
+    #[test]
+    fn try_sub() {
+        let a: SrcStr = "Götzen-Dämmerung".into();
+        let b = a.sub(8..a.len());
+
+        assert_eq!(Some(8..11), b.get(0..3).and_then(|s| s.range()));
+        assert_eq!(None, b.get(0..2).map(|s| s.to_string()));
+        assert_eq!(b.try_sub(1..2).unwrap_err(), RangeError::NotCharBoundary { index: 2 });
+        assert_eq!(b.try_sub(3..20).unwrap_err(), RangeError::OutOfBounds { range: 3..20, len: 10 });
+        assert_eq!("[1, 2]", format!("{:?}", Src::<[u8]>::from(vec![0u8, 1, 2, 3]).try_sub(1..3).unwrap()));
+    }
+
+    #[test]
+    fn try_src_sub() {
+        let a: SrcStr = "Also sprach Zarathustra".into();
+        let b = a.sub(12..);
+
+        assert_eq!("sprach", &b.try_src_sub(5..=10).unwrap()[..]);
+        assert_eq!("Also", &b.src_sub(..4)[..]);
+        assert_eq!(b.try_src_sub(20..30).unwrap_err(), RangeError::OutOfBounds { range: 20..30, len: 23 });
+        assert_eq!(RcSrcStr::from("süß").try_src_sub(2..).unwrap_err(), RangeError::NotCharBoundary { index: 2 });
+        assert_eq!(RcSrcStr::from_static("x").try_src_sub(..).unwrap_err(), RangeError::NoSource);
+    }
+
+    #[test]
+    fn pairs() {
+        let rc = Rc::new(String::from("Was mich nicht umbringt, macht mich stärker."));
+
+        let a = SrcStr::try_from((rc.clone(), 15..23)).unwrap();
+        assert_eq!("umbringt", &a[..]);
+        assert!(SrcStr::try_from((rc.clone(), 37..39)).is_err());
+
+        let b: SrcStr = (rc.clone(), ..).into();
+        assert_eq!(&b[..], &rc[..]);
+        assert_eq!(b.range(), Some(0..rc.len()));
+    }
+}
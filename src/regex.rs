@@ -0,0 +1,87 @@
+use alloc::vec::Vec;
+
+use ::regex::Regex;
+
+use crate::{SharedOwner, Src};
+
+// a match's capture groups as slices of the source. None for groups that
+// didn't take part in the match
+pub struct Captures<'r, P: SharedOwner<str>> {
+    regex: &'r Regex,
+    groups: Vec<Option<Src<str, P>>>,
+}
+
+impl<'r, P: SharedOwner<str>> Captures<'r, P> {
+    // group 0 is the whole match
+    pub fn get(&self, i: usize) -> Option<&Src<str, P>> {
+        self.groups.get(i)?.as_ref()
+    }
+
+    pub fn name(&self, name: &str) -> Option<&Src<str, P>> {
+        let i = self.regex.capture_names().position(|n| n == Some(name))?;
+        self.get(i)
+    }
+
+    // counting group 0, and groups that didn't match
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Option<&Src<str, P>>> + '_ {
+        self.groups.iter().map(Option::as_ref)
+    }
+}
+
+impl<P: SharedOwner<str>> Src<str, P> {
+    pub fn regex_find(&self, regex: &Regex) -> Option<Self> {
+        regex.find(self).map(|m| self.sub(m.range()))
+    }
+
+    pub fn regex_find_iter<'a>(&'a self, regex: &'a Regex) -> impl Iterator<Item = Self> + 'a {
+        regex.find_iter(self).map(move |m| self.sub(m.range()))
+    }
+
+    pub fn regex_captures<'r>(&self, regex: &'r Regex) -> Option<Captures<'r, P>> {
+        let captures = regex.captures(self)?;
+        let groups = captures.iter().map(|group| group.map(|m| self.sub(m.range()))).collect();
+        Some(Captures { regex, groups })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::SrcStr;
+
+    #[test]
+    fn regex_find() {
+        let a: SrcStr = "let x = 10; let y = 200;".into();
+        let b = a.sub(4..a.len());
+        let number = Regex::new(r"\d+").unwrap();
+
+        assert_eq!(Some(8..10), b.regex_find(&number).and_then(|m| m.range()));
+        let all: Vec<_> = b.regex_find_iter(&number).map(|m| m.range().unwrap()).collect();
+        assert_eq!(vec![8..10, 20..23], all);
+        assert!(b.regex_find(&Regex::new("z+").unwrap()).is_none());
+    }
+
+    #[test]
+    fn regex_captures() {
+        let a: SrcStr = "error[E0308]: mismatched types".into();
+        let diagnostic = Regex::new(r"(?P<level>\w+)(\[(?P<code>\w+)\])?(x)?: (.*)").unwrap();
+
+        let captures = a.regex_captures(&diagnostic).unwrap();
+        assert_eq!(6, captures.len());
+        assert_eq!(Some(0..30), captures.get(0).and_then(|m| m.range()));
+        assert_eq!("error", &captures.name("level").unwrap()[..]);
+        assert_eq!(Some(6..11), captures.name("code").and_then(|m| m.range()));
+        assert!(captures.get(4).is_none());
+        assert_eq!(5, captures.iter().flatten().count());
+        assert!(captures.name("nope").is_none());
+    }
+}
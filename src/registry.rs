@@ -0,0 +1,114 @@
+use core::convert::TryFrom;
+use core::ops::Range;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, SrcStr};
+
+// a small number standing in for an owner, cheap to hash and to serialize
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceId(u32);
+
+impl SourceId {
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+// hands out ids in registration order. owners are kept alive by the registry,
+// so their identities can't be reused by a later source
+pub struct SourceRegistry<P: SharedOwner<str> = Rc<String>> {
+    owners: Vec<P>,
+    ids: BTreeMap<usize, SourceId>, // owner identity -> id
+}
+
+impl<P: SharedOwner<str>> Default for SourceRegistry<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: SharedOwner<str>> SourceRegistry<P> {
+    pub fn new() -> Self {
+        Self { owners: Vec::new(), ids: BTreeMap::new() }
+    }
+
+    // None for 'static slices, which have no owner to register, and once all
+    // u32::MAX + 1 ids are taken
+    pub fn register(&mut self, src: &SrcStr<P>) -> Option<SourceId> {
+        let owner = src.src()?;
+        if let Some(&id) = self.ids.get(&owner.identity()) {
+            return Some(id);
+        }
+        let id = SourceId(u32::try_from(self.owners.len()).ok()?);
+        self.ids.insert(owner.identity(), id);
+        self.owners.push(owner.clone());
+        Some(id)
+    }
+
+    pub fn id_of(&self, src: &SrcStr<P>) -> Option<SourceId> {
+        self.ids.get(&src.src()?.identity()).copied()
+    }
+
+    pub fn owner(&self, id: SourceId) -> Option<&P> {
+        self.owners.get(id.0 as usize)
+    }
+
+    pub fn source(&self, id: SourceId) -> Option<SrcStr<P>> {
+        let owner = self.owner(id)?;
+        SrcStr::from_raw_parts(owner.clone(), 0..owner.elems().len())
+    }
+
+    pub fn span(&self, id: SourceId, range: Range<usize>) -> Option<SrcStr<P>> {
+        SrcStr::from_raw_parts(self.owner(id)?.clone(), range)
+    }
+
+    pub fn len(&self) -> usize {
+        self.owners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.owners.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RcSrcStr;
+
+    #[test]
+    fn register() {
+        let mut registry = SourceRegistry::new();
+        let a: SrcStr = "Beyond Good and Evil".into();
+        let b: SrcStr = "On the Genealogy of Morality".into();
+
+        let id = registry.register(&a.sub(7..11)).unwrap();
+        assert_eq!(Some(id), registry.register(&a));
+        assert_ne!(Some(id), registry.register(&b));
+        assert_eq!(Some(id), registry.id_of(&a.sub(0..6)));
+        assert_eq!(None, registry.register(&RcSrcStr::from_static("static")));
+
+        assert_eq!(Some(a.clone()), registry.source(id));
+        assert_eq!(Some(a.sub(7..11)), registry.span(id, 7..11));
+        assert_eq!(2, registry.len());
+    }
+
+    #[test]
+    fn empty_owners() {
+        let mut registry = SourceRegistry::new();
+        let (a, b): (SrcStr, SrcStr) = (String::new().into(), "".into());
+
+        let (id_a, id_b) = (registry.register(&a).unwrap(), registry.register(&b).unwrap());
+        assert_ne!(id_a, id_b);
+        assert_eq!(Some(id_b), registry.id_of(&b));
+        assert!(Rc::ptr_eq(a.src().unwrap(), registry.owner(id_a).unwrap()));
+        assert!(Rc::ptr_eq(b.src().unwrap(), registry.owner(id_b).unwrap()));
+    }
+}
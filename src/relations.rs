@@ -0,0 +1,266 @@
+use core::cmp::Ordering;
+use core::ops::Range;
+
+use crate::{Repr, SharedOwner, Slice, Src};
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Src<T, P> {
+    // which source a slice is in, and its element range there. all 'static
+    // slices count as one source, positioned by address
+    pub(crate) fn place(&self) -> (usize, Range<usize>) {
+        match self.repr {
+            Repr::Range { start, len } => (self.src_ptr() as usize, start..start + len),
+            Repr::Static(s) => {
+                let size = core::mem::size_of::<T::Elem>().max(1);
+                let at = s.as_elems().as_ptr() as usize / size;
+                (0, at..at + s.as_elems().len())
+            },
+        }
+    }
+
+    // the slice at `range` in this slice's source. for 'static slices the
+    // range has to stay within this or `other`
+    pub(crate) fn at_place(&self, other: &Self, range: Range<usize>) -> Option<Self> {
+        if let Repr::Range { .. } = self.repr {
+            let rc = self.rc.as_ref()?;
+            if !Self::valid_in(rc, range.clone()) {
+                return None;
+            }
+            let repr = Repr::Range { start: range.start, len: range.len() };
+            return Some(Self { rc: self.rc.clone(), repr, created: self.created });
+        }
+        // both 'static: the range has to lie within one of them, since two
+        // literals next to each other in memory are still separate objects
+        let (outer, at) = [self, other].iter().find_map(|s| match s.repr {
+            Repr::Static(outer) => {
+                let at = s.place().1;
+                (at.start <= range.start && range.end <= at.end).then_some((outer, at.start))
+            },
+            Repr::Range { .. } => None,
+        })?;
+        let elems = outer.as_elems().get(range.start - at..range.end - at)?;
+        let s = T::from_elems(elems)?;
+        // a piece of a 'static slice
+        let s = unsafe { &*(s as *const T) };
+        Some(Self { rc: self.rc.clone(), repr: Repr::Static(s), created: self.created })
+    }
+
+    // same owner; all 'static slices count as one source
+    pub fn same_source(&self, other: &Self) -> bool {
+        self.place().0 == other.place().0
+    }
+
+    pub fn is_within(&self, other: &Self) -> bool {
+        other.contains_span(self)
+    }
+
+    // from the earlier start to the later end. None across sources, or for
+    // 'static slices unless one contains the other
+    pub fn covering_span(&self, other: &Self) -> Option<Self> {
+        let ((key, a), (other_key, b)) = (self.place(), other.place());
+        if key != other_key {
+            return None;
+        }
+        self.at_place(other, a.start.min(b.start)..a.end.max(b.end))
+    }
+
+    // in elements, if parent contains self
+    pub fn offset_in(&self, parent: &Self) -> Option<usize> {
+        if !parent.contains_span(self) {
+            return None;
+        }
+        Some(self.place().1.start - parent.place().1.start)
+    }
+
+    // not `contains`, which would hide str::contains behind the deref
+    pub fn contains_span(&self, other: &Self) -> bool {
+        let ((key, a), (other_key, b)) = (self.place(), other.place());
+        key == other_key && a.start <= b.start && b.end <= a.end
+    }
+
+    // whether they share at least one element
+    pub fn overlaps(&self, other: &Self) -> bool {
+        let ((key, a), (other_key, b)) = (self.place(), other.place());
+        key == other_key && a.start < b.end && b.start < a.end
+    }
+
+    // None if they don't overlap, unless one is an empty slice inside the other
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if !self.overlaps(other) && !self.contains_span(other) && !other.contains_span(self) {
+            return None;
+        }
+        let (a, b) = (self.place().1, other.place().1);
+        self.at_place(other, a.start.max(b.start)..a.end.min(b.end))
+    }
+
+    // what separates the two, in either order. None if they touch or overlap,
+    // and for 'static slices, whose gap isn't known to be part of either
+    pub fn gap_between(&self, other: &Self) -> Option<Self> {
+        let ((key, a), (other_key, b)) = (self.place(), other.place());
+        if key != other_key {
+            return None;
+        }
+        let gap = if a.end < b.start { a.end..b.start } else { b.end..a.start };
+        if gap.start >= gap.end {
+            return None;
+        }
+        self.at_place(other, gap)
+    }
+
+    // for advancing through input with edit(): what was eaten from the start
+    // of origin up to self. panics unless self starts inside origin
+    pub fn consumed_since(&self, origin: &Self) -> Self {
+        let (start, at) = self.start_in(origin);
+        origin.at_place(self, start..at).expect("self does not start inside origin")
+    }
+
+    // what's left of origin from where self starts
+    pub fn remaining_of(&self, origin: &Self) -> Self {
+        let (_, at) = self.start_in(origin);
+        let end = origin.place().1.end;
+        origin.at_place(self, at..end).expect("self does not start inside origin")
+    }
+
+    // how far into origin self starts, from 0.0 to 1.0, for progress bars.
+    // an empty origin counts as done
+    pub fn progress_within(&self, origin: &Self) -> f64 {
+        let (start, at) = self.start_in(origin);
+        match origin.place().1.len() {
+            0 => 1.0,
+            len => (at - start) as f64 / len as f64,
+        }
+    }
+
+    fn start_in(&self, origin: &Self) -> (usize, usize) {
+        let ((key, a), (origin_key, o)) = (self.place(), origin.place());
+        assert!(key == origin_key && o.start <= a.start && a.start <= o.end, "self does not start inside origin");
+        (o.start, a.start)
+    }
+}
+
+// by (start, end) within a source. sources are ordered by address, which is
+// arbitrary but stable while they're alive
+impl<T: ?Sized + Slice, P: SharedOwner<T>> Ord for Src<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |s: &Self| {
+            let (source, range) = s.place();
+            (source, range.start, range.end, s.src_ptr() as usize)
+        };
+        key(self).cmp(&key(other))
+    }
+}
+
+impl<T: ?Sized + Slice, P: SharedOwner<T>> PartialOrd for Src<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RcSrcStr, SrcStr};
+
+    #[test]
+    fn covering_span() {
+        let a: SrcStr = "fn add(a: u32, b: u32) -> u32".into();
+        let (name, ret) = (a.sub(3..6), a.sub(26..29));
+
+        assert_eq!(Some(a.sub(3..29)), name.covering_span(&ret));
+        assert_eq!(Some(a.sub(3..29)), ret.covering_span(&name));
+        assert_eq!(None, name.covering_span(&"add".into()));
+
+        let s = RcSrcStr::from_static("static text");
+        assert_eq!(Some(s.clone()), s.covering_span(&s.sub(3..7)));
+        assert_eq!(None, s.sub(0..3).covering_span(&s.sub(7..11)));
+    }
+
+    #[test]
+    fn overlaps() {
+        let a: SrcStr = "if x { y } else { z }".into();
+        let (block, y, tail) = (a.sub(5..10), a.sub(7..8), a.sub(9..15));
+
+        assert!(block.contains_span(&y));
+        assert!(!y.contains_span(&block));
+        assert!(block.overlaps(&tail));
+        assert!(!y.overlaps(&tail));
+        assert_eq!(Some(a.sub(9..10)), block.intersection(&tail));
+        assert_eq!(None, y.intersection(&tail));
+        assert_eq!(Some(a.sub(6..6)), block.intersection(&a.sub(6..6)));
+
+        let other: SrcStr = "if x { y } else { z }".into();
+        assert!(!block.overlaps(&other) && !other.contains_span(&y));
+    }
+
+    #[test]
+    fn same_source() {
+        let a: SrcStr = "Ecce homo".into();
+        let b: SrcStr = "Ecce homo".into();
+        let s = RcSrcStr::from_static("Ecce homo");
+
+        assert!(a.sub(0..4).same_source(&a.sub(5..9)));
+        assert!(!a.same_source(&b));
+        assert!(!a.same_source(&s));
+        assert!(s.sub(5..9).same_source(&s));
+        assert!(s.sub(5..9).is_within(&s));
+        assert!(a.sub(5..9).is_within(&a.sub(4..9)));
+        assert!(!a.sub(5..9).is_within(&b));
+    }
+
+    #[test]
+    fn ord() {
+        let a: SrcStr = "b a c".into();
+        let mut tokens = alloc::vec![a.sub(4..5), a.sub(0..1), a.sub(2..3), a.sub(0..3)];
+        tokens.sort();
+
+        assert_eq!(alloc::vec![a.sub(0..1), a.sub(0..3), a.sub(2..3), a.sub(4..5)], tokens);
+        assert_eq!(core::cmp::Ordering::Equal, a.sub(2..3).cmp(&a.sub(2..3)));
+
+        let b: SrcStr = "b a c".into();
+        assert_ne!(core::cmp::Ordering::Equal, a.cmp(&b));
+    }
+
+    #[test]
+    fn offset_in() {
+        let a: SrcStr = "outer { inner { x } }".into();
+        let inner = a.sub(8..19);
+        let x = a.sub(16..17);
+
+        assert_eq!(Some(8), x.offset_in(&inner));
+        assert_eq!(Some(16), x.offset_in(&a));
+        assert_eq!(None, inner.offset_in(&x));
+    }
+
+    #[test]
+    fn gap_between() {
+        let a: SrcStr = "let x =  1;".into();
+        let (x, one) = (a.sub(4..5), a.sub(9..10));
+
+        assert_eq!(" =  ", &x.gap_between(&one).unwrap()[..]);
+        assert_eq!(Some(a.sub(5..9)), one.gap_between(&x));
+        assert_eq!(None, x.gap_between(&a.sub(5..6)));
+        assert_eq!(None, x.gap_between(&a.sub(0..6)));
+    }
+
+    #[test]
+    fn consumed_since() {
+        let origin: SrcStr = "let x = 1;".into();
+        let mut rest = origin.clone();
+        rest.edit(|s| *s = &s[4..]);
+
+        assert_eq!(Some(0..4), rest.consumed_since(&origin).range());
+        assert_eq!("= 1;", &rest.sub(2..3).remaining_of(&origin)[..]);
+        assert_eq!("let x = ", &rest.sub(4..5).consumed_since(&origin)[..]);
+
+        let xyz = RcSrcStr::from_static("xyz");
+        assert_eq!("yz", &xyz.sub(1..2).remaining_of(&xyz)[..]);
+    }
+
+    #[test]
+    fn progress_within() {
+        let origin: SrcStr = "abcdefgh".into();
+
+        assert_eq!(0.0, origin.progress_within(&origin));
+        assert_eq!(0.25, origin.sub(2..3).progress_within(&origin));
+        assert_eq!(1.0, origin.sub(8..8).progress_within(&origin));
+        assert_eq!(1.0, origin.sub(3..3).progress_within(&origin.sub(3..3)));
+    }
+}
@@ -0,0 +1,60 @@
+use core::ops::Deref;
+
+use crate::{SharedOwner, Src, SrcPattern};
+
+// pattern searches giving each match as a slice of the source rather than
+// an offset into this one
+impl<P: SharedOwner<str>> Src<str, P> {
+    pub fn find_spanned<Pat: SrcPattern>(&self, pattern: Pat) -> Option<Self> {
+        self.find_iter(pattern).next()
+    }
+
+    pub fn rfind_spanned<Pat: SrcPattern>(&self, pattern: Pat) -> Option<Self> {
+        self.rmatch_indices_src(pattern).next().map(|(_, m)| m)
+    }
+
+    // non-overlapping, front to back
+    pub fn find_iter<'a, Pat: SrcPattern + 'a>(&'a self, pattern: Pat) -> impl Iterator<Item = Self> + 'a {
+        self.match_indices_src(pattern).map(|(_, m)| m)
+    }
+
+    // str's match_indices, offsets relative to this slice as there, but with
+    // each match as a slice of the source
+    pub fn match_indices_src<'a, Pat: SrcPattern + 'a>(&'a self, pattern: Pat) -> impl Iterator<Item = (usize, Self)> + 'a {
+        pattern.match_indices(self.deref()).map(move |(i, m)| (i, self.subslice(m)))
+    }
+
+    pub fn rmatch_indices_src<'a, Pat: SrcPattern + 'a>(&'a self, pattern: Pat) -> impl Iterator<Item = (usize, Self)> + 'a {
+        pattern.rmatch_indices(self.deref()).map(move |(i, m)| (i, self.subslice(m)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use crate::SrcStr;
+
+    #[test]
+    fn find_iter() {
+        let a: SrcStr = "x = foo(foo, bar) + foo".into();
+        let b = a.sub(4..a.len());
+
+        let found: Vec<_> = b.find_iter("foo").map(|m| m.range().unwrap()).collect();
+        assert_eq!(vec![4..7, 8..11, 20..23], found);
+        assert_eq!(6, b.match_indices_src('o').count());
+        let back: Vec<_> = b.rmatch_indices_src("foo").map(|(i, m)| (i, m.range().unwrap())).collect();
+        assert_eq!(vec![(16, 20..23), (4, 8..11), (0, 4..7)], back);
+        assert_eq!(vec![(0, "foo"), (4, "foo"), (16, "foo")], b.match_indices("foo").collect::<Vec<(usize, &str)>>());
+    }
+
+    #[test]
+    fn find_spanned() {
+        let a: SrcStr = "let total = total + 1;".into();
+
+        assert_eq!(Some(4..9), a.find_spanned("total").and_then(|m| m.range()));
+        assert_eq!(Some(12..17), a.rfind_spanned("total").and_then(|m| m.range()));
+        assert_eq!(Some(21..22), a.find_spanned(|c: char| c == ';' || c == '!').and_then(|m| m.range()));
+        assert!(a.find_spanned("minus").is_none());
+    }
+}
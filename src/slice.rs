@@ -0,0 +1,73 @@
+use core::ops::Range;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// # Safety
+///
+/// `get` and `from_elems` must only return `Some` for element runs that
+/// `from_elems_unchecked` accepts, and `as_elems` must give back exactly the
+/// elements a slice was made from.
+pub unsafe trait Slice: 'static {
+    type Elem: 'static;
+    type Owned;
+
+    fn as_elems(&self) -> &[Self::Elem];
+    fn empty() -> &'static Self;
+    fn get(&self, index: Range<usize>) -> Option<&Self>;
+    fn from_elems(elems: &[Self::Elem]) -> Option<&Self>;
+
+    /// # Safety
+    ///
+    /// `elems` must be something `from_elems` would accept.
+    unsafe fn from_elems_unchecked(elems: &[Self::Elem]) -> &Self;
+}
+
+unsafe impl Slice for str {
+    type Elem = u8;
+    type Owned = String;
+
+    fn as_elems(&self) -> &[u8] {
+        self.as_bytes()
+    }
+
+    fn empty() -> &'static str {
+        ""
+    }
+
+    fn get(&self, index: Range<usize>) -> Option<&str> {
+        str::get(self, index)
+    }
+
+    fn from_elems(elems: &[u8]) -> Option<&str> {
+        core::str::from_utf8(elems).ok()
+    }
+
+    unsafe fn from_elems_unchecked(elems: &[u8]) -> &str {
+        core::str::from_utf8_unchecked(elems)
+    }
+}
+
+unsafe impl<E: 'static> Slice for [E] {
+    type Elem = E;
+    type Owned = Vec<E>;
+
+    fn as_elems(&self) -> &[E] {
+        self
+    }
+
+    fn empty() -> &'static [E] {
+        &[]
+    }
+
+    fn get(&self, index: Range<usize>) -> Option<&[E]> {
+        <[E]>::get(self, index)
+    }
+
+    fn from_elems(elems: &[E]) -> Option<&[E]> {
+        Some(elems)
+    }
+
+    unsafe fn from_elems_unchecked(elems: &[E]) -> &[E] {
+        elems
+    }
+}
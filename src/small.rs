@@ -0,0 +1,109 @@
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use alloc::rc::Rc;
+use alloc::string::String;
+
+use crate::provenance::Provenance;
+use crate::{Repr, SharedOwner, Src, SrcStr};
+
+// like SrcStr, but with u32 offsets, so sources are limited to 4 GiB
+#[derive(Clone)]
+pub struct SmallSrcStr<P: SharedOwner<str> = Rc<String>> {
+    rc: P,
+    start: u32,
+    len: u32,
+}
+
+impl<P: SharedOwner<str>> SmallSrcStr<P> {
+    pub fn src(&self) -> &P {
+        &self.rc
+    }
+
+    pub fn range(&self) -> core::ops::Range<usize> {
+        self.start as usize..(self.start + self.len) as usize
+    }
+}
+
+impl<P: SharedOwner<str>> Deref for SmallSrcStr<P> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        // only ever built from a SrcStr range, which is already checked
+        unsafe { core::str::from_utf8_unchecked(self.rc.elems().get_unchecked(self.range())) }
+    }
+}
+
+impl<P: SharedOwner<str>> PartialEq for SmallSrcStr<P> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.rc.elems().as_ptr() == rhs.rc.elems().as_ptr() && self.start == rhs.start && self.len == rhs.len
+    }
+}
+impl<P: SharedOwner<str>> Eq for SmallSrcStr<P> {}
+
+impl<P: SharedOwner<str>> Hash for SmallSrcStr<P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rc.elems().as_ptr().hash(state);
+        (self.start, self.len).hash(state);
+    }
+}
+
+impl<P: SharedOwner<str>> fmt::Debug for SmallSrcStr<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.deref())
+    }
+}
+
+impl<P: SharedOwner<str>> From<SmallSrcStr<P>> for SrcStr<P> {
+    fn from(small: SmallSrcStr<P>) -> Self {
+        let repr = Repr::Range { start: small.start as usize, len: small.len as usize };
+        Src { rc: Some(small.rc), repr, created: Provenance::NONE }
+    }
+}
+
+// gives the SrcStr back if it's 'static, or doesn't fit in u32 offsets
+impl<P: SharedOwner<str>> TryFrom<SrcStr<P>> for SmallSrcStr<P> {
+    type Error = SrcStr<P>;
+    fn try_from(s: SrcStr<P>) -> Result<Self, SrcStr<P>> {
+        let (start, len) = match s.repr {
+            Repr::Range { start, len } => (start, len),
+            Repr::Static(_) => return Err(s),
+        };
+        match (u32::try_from(start), u32::try_from(start + len)) {
+            (Ok(start), Ok(_)) => {
+                let rc = s.rc.expect("ranged SrcStr without an owner");
+                Ok(SmallSrcStr { rc, start, len: len as u32 })
+            },
+            _ => Err(s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryInto;
+
+    #[test]
+    fn round_trip() {
+        let a: SrcStr = "There are no facts, only interpretations.".into();
+        let b = a.sub(13..18);
+
+        let small: SmallSrcStr = b.clone().try_into().unwrap();
+        assert_eq!("facts", &small[..]);
+        assert_eq!(small.range(), 13..18);
+        assert_eq!(SrcStr::from(small), b);
+
+        // provenance makes SrcStr bigger
+        #[cfg(not(feature = "provenance"))]
+        assert_eq!(core::mem::size_of::<SmallSrcStr>() * 2, core::mem::size_of::<SrcStr>());
+    }
+
+    #[test]
+    fn static_slice() {
+        let mut a: SrcStr = "Without music, life would be a mistake.".into();
+        a.edit(|s| *s = "silence");
+
+        assert!(SmallSrcStr::try_from(a).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use core::ops::Range;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, SrcStr};
+
+// many files laid out one after another in a single global position space,
+// with a one byte gap after each so end-of-file positions stay unambiguous
+pub struct SourceMap<P: SharedOwner<str> = Rc<String>> {
+    files: Vec<(usize, SrcStr<P>)>, // (global start, file)
+    next: usize,
+}
+
+// a global position resolved back to its file. line and column count from 1,
+// the column in chars
+#[derive(Clone, Debug)]
+pub struct Loc<P: SharedOwner<str> = Rc<String>> {
+    pub file: SrcStr<P>,
+    pub span: SrcStr<P>,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl<P: SharedOwner<str>> Default for SourceMap<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: SharedOwner<str>> SourceMap<P> {
+    pub fn new() -> Self {
+        Self { files: Vec::new(), next: 0 }
+    }
+
+    // returns the global position the file starts at
+    pub fn add(&mut self, file: SrcStr<P>) -> usize {
+        let start = self.next;
+        self.next += file.len() + 1;
+        self.files.push((start, file));
+        start
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &SrcStr<P>> + '_ {
+        self.files.iter().map(|(_, file)| file)
+    }
+
+    fn file_at(&self, pos: usize) -> Option<&(usize, SrcStr<P>)> {
+        let i = self.files.partition_point(|(start, _)| *start <= pos).checked_sub(1)?;
+        let entry = &self.files[i];
+        if pos - entry.0 <= entry.1.len() {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    pub fn lookup(&self, pos: usize) -> Option<Loc<P>> {
+        let (start, file) = self.file_at(pos)?;
+        let offset = pos - start;
+        if !file.is_char_boundary(offset) {
+            return None;
+        }
+        let before = &file[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Some(Loc {
+            file: file.clone(),
+            span: file.sub(offset..offset),
+            line: before.matches('\n').count() + 1,
+            col: before[line_start..].chars().count() + 1,
+        })
+    }
+
+    // the global range must fall within one file
+    pub fn span(&self, range: Range<usize>) -> Option<SrcStr<P>> {
+        let (start, file) = self.file_at(range.start)?;
+        let local = range.start - start..range.end.checked_sub(*start)?;
+        file.get(local.clone())?;
+        Some(file.sub(local))
+    }
+
+    // where a span taken from one of the files sits in the global space
+    pub fn global_range(&self, span: &SrcStr<P>) -> Option<Range<usize>> {
+        let range = span.range()?;
+        self.files.iter().find_map(|(start, file)| {
+            let outer = file.range()?;
+            if file.src_ptr() == span.src_ptr() && outer.start <= range.start && range.end <= outer.end {
+                let offset = start + range.start - outer.start;
+                Some(offset..offset + range.len())
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let mut map = SourceMap::new();
+        let a: SrcStr = "Aphorisms\non the\nwill".into();
+        let b: SrcStr = "and the eternal\nreturn".into();
+        assert_eq!(0, map.add(a.clone()));
+        assert_eq!(22, map.add(b.clone()));
+
+        let loc = map.lookup(19).unwrap();
+        assert_eq!(loc.file, a);
+        assert_eq!((3, 3), (loc.line, loc.col));
+
+        let loc = map.lookup(22 + 18).unwrap();
+        assert_eq!(loc.file, b);
+        assert_eq!((2, 3), (loc.line, loc.col));
+        assert_eq!("return", &map.span(22 + 16..22 + 22).unwrap()[..]);
+
+        assert!(map.lookup(200).is_none());
+        assert!(map.span(20..25).is_none());
+    }
+
+    #[test]
+    fn global_range() {
+        let mut map = SourceMap::new();
+        map.add("first file".into());
+        let b: SrcStr = "second file".into();
+        let start = map.add(b.clone());
+
+        assert_eq!(Some(start + 7..start + 11), map.global_range(&b.sub(7..11)));
+        assert_eq!(None, map.global_range(&"elsewhere".into()));
+    }
+}
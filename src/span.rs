@@ -0,0 +1,64 @@
+use core::convert::TryFrom;
+use core::ops::Range;
+
+use crate::{SharedOwner, SourceId, SourceRegistry, SrcStr};
+
+// a slice without its text: 12 bytes for storing in ASTs, turned back into a
+// SrcStr through the registry that handed out its SourceId
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    pub source_id: SourceId,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Span {
+    pub fn new(source_id: SourceId, range: Range<usize>) -> Option<Self> {
+        if range.start > range.end {
+            return None;
+        }
+        let start = u32::try_from(range.start).ok()?;
+        let end = u32::try_from(range.end).ok()?;
+        Some(Self { source_id, start, end })
+    }
+
+    pub fn range(self) -> Range<usize> {
+        self.start as usize..self.end as usize
+    }
+
+    pub fn len(self) -> usize {
+        (self.end - self.start) as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl<P: SharedOwner<str>> SourceRegistry<P> {
+    // registers the owner if it's new. None for 'static slices, or offsets past u32
+    pub fn to_span(&mut self, src: &SrcStr<P>) -> Option<Span> {
+        let id = self.register(src)?;
+        Span::new(id, src.range()?)
+    }
+
+    pub fn hydrate(&self, span: Span) -> Option<SrcStr<P>> {
+        self.span(span.source_id, span.range())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hydrate() {
+        let mut registry = SourceRegistry::new();
+        let a: SrcStr = "Thus Spoke Zarathustra".into();
+
+        let span = registry.to_span(&a.sub(5..10)).unwrap();
+        assert_eq!(5..10, span.range());
+        assert_eq!(Some(a.sub(5..10)), registry.hydrate(span));
+        assert_eq!(12, core::mem::size_of::<Span>());
+    }
+}
@@ -0,0 +1,135 @@
+use core::ops::Range;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, SrcStr};
+
+// a set of byte ranges over one source, kept sorted and merged
+#[derive(Clone)]
+pub struct SpanSet<P: SharedOwner<str> = Rc<String>> {
+    src: SrcStr<P>,
+    ranges: Vec<Range<usize>>, // relative to src, disjoint and not touching
+}
+
+impl<P: SharedOwner<str>> SpanSet<P> {
+    pub fn new(src: SrcStr<P>) -> Self {
+        Self { src, ranges: Vec::new() }
+    }
+
+    pub fn src(&self) -> &SrcStr<P> {
+        &self.src
+    }
+
+    // spans from elsewhere are ignored, and so is false returned
+    pub fn insert(&mut self, span: &SrcStr<P>) -> bool {
+        match span.offset_in(&self.src) {
+            Some(start) => {
+                self.insert_range(start..start + span.len());
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn insert_range(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        // everything touching the new range merges into it
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = match self.ranges[first..last].first().zip(self.ranges[first..last].last()) {
+            Some((a, b)) => a.start.min(range.start)..b.end.max(range.end),
+            None => range,
+        };
+        self.ranges.splice(first..last, Some(merged));
+    }
+
+    pub fn union(&mut self, other: &Self) {
+        for range in &other.ranges {
+            self.insert_range(range.clone());
+        }
+    }
+
+    pub fn subtract_range(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let mut kept = Vec::with_capacity(self.ranges.len() + 1);
+        for r in self.ranges.drain(..) {
+            if r.end <= range.start || range.end <= r.start {
+                kept.push(r);
+                continue;
+            }
+            if r.start < range.start {
+                kept.push(r.start..range.start);
+            }
+            if range.end < r.end {
+                kept.push(range.end..r.end);
+            }
+        }
+        self.ranges = kept;
+    }
+
+    pub fn subtract(&mut self, other: &Self) {
+        for range in &other.ranges {
+            self.subtract_range(range.clone());
+        }
+    }
+
+    pub fn covers(&self, offset: usize) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= offset);
+        self.ranges.get(i).is_some_and(|r| r.start <= offset)
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.ranges.iter().cloned()
+    }
+
+    // the merged ranges as slices of the source. ranges that don't fall on
+    // char boundaries are skipped
+    pub fn spans(&self) -> impl Iterator<Item = SrcStr<P>> + '_ {
+        self.ranges.iter().filter_map(move |r| {
+            self.src.get(r.clone())?;
+            Some(self.src.sub(r.clone()))
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn merge() {
+        let a: SrcStr = "0123456789abcdef".into();
+        let mut set = SpanSet::new(a.clone());
+        set.insert(&a.sub(2..4));
+        set.insert(&a.sub(8..10));
+        set.insert_range(4..6);
+        assert_eq!(vec![2..6, 8..10], set.ranges().collect::<Vec<_>>());
+
+        set.insert_range(5..9);
+        assert_eq!(vec![2..10], set.ranges().collect::<Vec<_>>());
+        assert!(!set.insert(&"elsewhere".into()));
+
+        set.subtract_range(4..6);
+        assert_eq!(vec![2..4, 6..10], set.ranges().collect::<Vec<_>>());
+        assert!(set.covers(3) && !set.covers(4) && set.covers(9) && !set.covers(10));
+        assert_eq!(vec!["23", "6789"], set.spans().map(|s| s.to_string()).collect::<Vec<_>>());
+
+        let mut other = SpanSet::new(a);
+        other.insert_range(0..3);
+        set.union(&other);
+        assert_eq!(vec![0..4, 6..10], set.ranges().collect::<Vec<_>>());
+        set.subtract(&other);
+        assert_eq!(vec![3..4, 6..10], set.ranges().collect::<Vec<_>>());
+    }
+}
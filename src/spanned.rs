@@ -0,0 +1,119 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+
+use crate::{SharedOwner, SrcStr};
+
+// anything that knows where in the source it came from. `#[derive(HasSpan)]`
+// (with the `derive` feature) covers the struct's `#[span]` fields
+pub trait HasSpan {
+    type Owner: SharedOwner<str>;
+
+    fn span(&self) -> SrcStr<Self::Owner>;
+}
+
+impl<P: SharedOwner<str>> HasSpan for SrcStr<P> {
+    type Owner = P;
+
+    fn span(&self) -> SrcStr<P> {
+        self.clone()
+    }
+}
+
+impl<S: HasSpan + ?Sized> HasSpan for Box<S> {
+    type Owner = S::Owner;
+
+    fn span(&self) -> SrcStr<S::Owner> {
+        (**self).span()
+    }
+}
+
+// a value and the text it was parsed from
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Spanned<T, P: SharedOwner<str> = Rc<String>> {
+    pub value: T,
+    pub span: SrcStr<P>,
+}
+
+impl<T, P: SharedOwner<str>> Spanned<T, P> {
+    pub fn new(value: T, span: SrcStr<P>) -> Self {
+        Self { value, span }
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Spanned<U, P> {
+        Spanned { value: f(self.value), span: self.span }
+    }
+
+    pub fn as_ref(&self) -> Spanned<&T, P> {
+        Spanned { value: &self.value, span: self.span.clone() }
+    }
+
+    pub fn as_mut(&mut self) -> Spanned<&mut T, P> {
+        Spanned { value: &mut self.value, span: self.span.clone() }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, P: SharedOwner<str>> HasSpan for Spanned<T, P> {
+    type Owner = P;
+
+    fn span(&self) -> SrcStr<P> {
+        self.span.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spanned() {
+        let a: SrcStr = "x = 42".into();
+        let n = Spanned::new("42", a.sub(4..6));
+
+        let n = n.map(|s| s.parse::<u32>().unwrap());
+        assert_eq!(42, n.value);
+        assert_eq!(Some(4..6), n.as_ref().span.range());
+        assert_eq!(n.span, Box::new(n.clone()).span());
+        *n.clone().as_mut().value += 1;
+        assert_eq!(42, n.into_inner());
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive() {
+        #[derive(crate::HasSpan)]
+        struct Assign {
+            #[span]
+            name: SrcStr,
+            #[span]
+            value: Spanned<u32>,
+            _note: &'static str,
+        }
+
+        #[derive(crate::HasSpan)]
+        enum Expr {
+            Lit(#[span] Spanned<u32>),
+            Add(#[span] Box<Expr>, #[span] Box<Expr>),
+        }
+
+        let a: SrcStr = "x = 1 + 2".into();
+        let assign = Assign { name: a.sub(0..1), value: Spanned::new(1, a.sub(4..5)), _note: "" };
+        assert_eq!(Some(0..5), assign.span().range());
+
+        let lit = |range| Box::new(Expr::Lit(Spanned::new(0, a.sub(range))));
+        assert_eq!(Some(4..9), Expr::Add(lit(4..5), lit(8..9)).span().range());
+
+        #[derive(crate::HasSpan)]
+        struct Pair<T> {
+            #[span]
+            left: T,
+            #[span]
+            right: T,
+        }
+        assert_eq!(Some(0..9), Pair { left: a.sub(0..1), right: a.sub(8..9) }.span().range());
+    }
+}
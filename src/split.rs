@@ -0,0 +1,137 @@
+use core::ops::Deref;
+
+use crate::{SharedOwner, Src, SrcPattern};
+
+// str's splitting methods, with each piece a slice of the source. suffixed
+// _src so the str ones, reached through the deref, keep working
+impl<P: SharedOwner<str>> Src<str, P> {
+    pub fn split_src<'a, Pat: SrcPattern + 'a>(&'a self, pattern: Pat) -> impl Iterator<Item = Self> + 'a {
+        pattern.split(self.deref()).map(move |piece| self.subslice(piece))
+    }
+
+    pub fn splitn_src<'a, Pat: SrcPattern + 'a>(&'a self, n: usize, pattern: Pat) -> impl Iterator<Item = Self> + 'a {
+        pattern.splitn(self.deref(), n).map(move |piece| self.subslice(piece))
+    }
+
+    pub fn rsplit_src<'a, Pat: SrcPattern + 'a>(&'a self, pattern: Pat) -> impl Iterator<Item = Self> + 'a {
+        pattern.rsplit(self.deref()).map(move |piece| self.subslice(piece))
+    }
+
+    pub fn rsplitn_src<'a, Pat: SrcPattern + 'a>(&'a self, n: usize, pattern: Pat) -> impl Iterator<Item = Self> + 'a {
+        pattern.rsplitn(self.deref(), n).map(move |piece| self.subslice(piece))
+    }
+
+    pub fn split_terminator_src<'a, Pat: SrcPattern + 'a>(&'a self, pattern: Pat) -> impl Iterator<Item = Self> + 'a {
+        pattern.split_terminator(self.deref()).map(move |piece| self.subslice(piece))
+    }
+
+    pub fn split_whitespace_src(&self) -> impl Iterator<Item = Self> + '_ {
+        self.deref().split_whitespace().map(move |word| self.subslice(word))
+    }
+
+    pub fn split_ascii_whitespace_src(&self) -> impl Iterator<Item = Self> + '_ {
+        self.deref().split_ascii_whitespace().map(move |word| self.subslice(word))
+    }
+
+    pub fn split_once_src<Pat: SrcPattern>(&self, delimiter: Pat) -> Option<(Self, Self)> {
+        let (a, b) = delimiter.split_once(self.deref())?;
+        Some((self.subslice(a), self.subslice(b)))
+    }
+
+    pub fn rsplit_once_src<Pat: SrcPattern>(&self, delimiter: Pat) -> Option<(Self, Self)> {
+        let (a, b) = delimiter.rsplit_once(self.deref())?;
+        Some((self.subslice(a), self.subslice(b)))
+    }
+
+    pub fn strip_prefix_src<Pat: SrcPattern>(&self, prefix: Pat) -> Option<Self> {
+        Some(self.split_prefix(prefix)?.1)
+    }
+
+    pub fn strip_suffix_src<Pat: SrcPattern>(&self, suffix: Pat) -> Option<Self> {
+        Some(self.split_suffix(suffix)?.0)
+    }
+
+    // strip_prefix_src, also keeping what was stripped: (prefix, rest)
+    pub fn split_prefix<Pat: SrcPattern>(&self, prefix: Pat) -> Option<(Self, Self)> {
+        let rest = prefix.strip_prefix(self.deref())?;
+        Some(self.split_at(self.len() - rest.len()))
+    }
+
+    // (rest, suffix)
+    pub fn split_suffix<Pat: SrcPattern>(&self, suffix: Pat) -> Option<(Self, Self)> {
+        let rest = suffix.strip_suffix(self.deref())?;
+        Some(self.split_at(rest.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use crate::SrcStr;
+
+    #[test]
+    fn split_once_src() {
+        let a: SrcStr = "name: value: more".into();
+
+        let (key, value) = a.split_once_src(": ").unwrap();
+        assert_eq!(("name", "value: more"), (&key[..], &value[..]));
+        assert_eq!(Some(6..17), value.range());
+
+        let (rest, last) = a.rsplit_once_src(':').unwrap();
+        assert_eq!(("name: value", " more"), (&rest[..], &last[..]));
+        assert!(a.split_once_src('=').is_none());
+    }
+
+    #[test]
+    fn split_src() {
+        let a: SrcStr = "name,age, city".into();
+        let fields: Vec<_> = a.split_src(',').collect();
+
+        assert_eq!(vec!["name", "age", " city"], fields.iter().map(|f| &f[..]).collect::<Vec<_>>());
+        assert_eq!(Some(9..14), fields[2].range());
+        assert_eq!(vec![Some(0..8), Some(10..14)], a.split_src(", ").map(|f| f.range()).collect::<Vec<_>>());
+        assert_eq!(4, a.split_src(|c: char| c == ',' || c == ' ').count());
+        assert_eq!(1, a.split_src(&[' ', ','][..]).filter(|f| f.is_empty()).count());
+        // str's own split is still there through the deref
+        assert_eq!(vec!["name", "age", " city"], a.split(',').collect::<Vec<&str>>());
+    }
+
+    #[test]
+    fn splitn_src() {
+        let a: SrcStr = "a=b=c;".into();
+        let ranges = |it: &mut dyn Iterator<Item = SrcStr>| it.map(|s| s.range().unwrap()).collect::<Vec<_>>();
+
+        assert_eq!(vec![0..1, 2..6], ranges(&mut a.splitn_src(2, '=')));
+        assert_eq!(vec![4..6, 2..3, 0..1], ranges(&mut a.rsplit_src('=')));
+        assert_eq!(vec![4..6, 0..3], ranges(&mut a.rsplitn_src(2, '=')));
+        assert_eq!(vec![0..5], ranges(&mut a.split_terminator_src(';')));
+        assert_eq!(2, a.split_src(';').count());
+    }
+
+    #[test]
+    fn split_whitespace_src() {
+        let a: SrcStr = " git\tcommit \u{3000}-m  x\n".into();
+
+        let words: Vec<_> = a.split_whitespace_src().map(|w| w.range().unwrap()).collect();
+        assert_eq!(vec![1..4, 5..11, 15..17, 19..20], words);
+        let ascii: Vec<_> = a.split_ascii_whitespace_src().map(|w| w.to_string()).collect();
+        assert_eq!(vec!["git", "commit", "\u{3000}-m", "x"], ascii);
+    }
+
+    #[test]
+    fn strip_prefix_src() {
+        let a: SrcStr = "--verbose=2".into();
+
+        assert_eq!(Some(2..11), a.strip_prefix_src("--").and_then(|flag| flag.range()));
+        let (dashes, flag) = a.split_prefix(|c: char| c.is_ascii_punctuation()).unwrap();
+        assert_eq!(("-", "-verbose=2"), (&dashes[..], &flag[..]));
+        assert!(a.strip_prefix_src("-v").is_none());
+
+        assert_eq!("--verbose", &a.strip_suffix_src("=2").unwrap()[..]);
+        let (rest, digit) = a.split_suffix(char::is_numeric).unwrap();
+        assert_eq!((Some(0..10), Some(10..11)), (rest.range(), digit.range()));
+        assert!(a.strip_suffix_src('=').is_none());
+    }
+}
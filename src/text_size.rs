@@ -0,0 +1,61 @@
+use core::convert::TryFrom;
+use core::ops::Range;
+
+use text_size::{TextRange, TextSize};
+
+use crate::{RangeError, SharedOwner, Src};
+
+fn to_range(range: TextRange) -> Range<usize> {
+    range.start().into()..range.end().into()
+}
+
+impl<P: SharedOwner<str>> Src<str, P> {
+    // the range in the owner, as rowan and friends spell it. None for 'static
+    // slices, and for sources past u32::MAX
+    pub fn text_range(&self) -> Option<TextRange> {
+        let range = self.range()?;
+        let (start, end) = (TextSize::try_from(range.start).ok()?, TextSize::try_from(range.end).ok()?);
+        Some(TextRange::new(start, end))
+    }
+
+    // like sub, relative to this slice
+    pub fn sub_text_range(&self, range: TextRange) -> Self {
+        self.sub(to_range(range))
+    }
+}
+
+impl<P: SharedOwner<str>> TryFrom<(P, TextRange)> for Src<str, P> {
+    type Error = RangeError;
+
+    fn try_from((rc, range): (P, TextRange)) -> Result<Self, RangeError> {
+        Self::new(rc, to_range(range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use crate::{RcSrcStr, SrcStr};
+
+    #[test]
+    fn text_range() {
+        let a: SrcStr = "fn götterdämmerung() {}".into();
+        let name = a.sub(3..20);
+
+        assert_eq!(Some(TextRange::new(3.into(), 20.into())), name.text_range());
+        assert_eq!("dämmerung", &name.sub_text_range(TextRange::new(7.into(), 17.into()))[..]);
+        assert_eq!(None, RcSrcStr::from_static("x").text_range());
+    }
+
+    #[test]
+    fn try_from() {
+        let rc = Rc::new(String::from("let x = 1;"));
+
+        let a = SrcStr::try_from((rc.clone(), TextRange::new(4.into(), 5.into()))).unwrap();
+        assert_eq!("x", &a[..]);
+        assert_eq!(a.text_range(), Some(TextRange::new(4.into(), 5.into())));
+        assert!(SrcStr::try_from((rc, TextRange::up_to(20.into()))).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use core::fmt;
+use core::str::Utf8Error;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{SharedOwner, Src, SrcStr};
+
+// the text before the first invalid byte, as its own source
+pub struct Utf8SpanError<P: SharedOwner<str> = Rc<String>> {
+    valid: SrcStr<P>,
+    error: Utf8Error,
+}
+
+impl<P: SharedOwner<str>> Utf8SpanError<P> {
+    pub fn valid_up_to(&self) -> usize {
+        self.error.valid_up_to()
+    }
+
+    pub fn valid(&self) -> &SrcStr<P> {
+        &self.valid
+    }
+
+    pub fn into_valid(self) -> SrcStr<P> {
+        self.valid
+    }
+
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+impl<P: SharedOwner<str>> fmt::Debug for Utf8SpanError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Utf8SpanError").field("valid", &self.valid).field("error", &self.error).finish()
+    }
+}
+
+impl<P: SharedOwner<str>> fmt::Display for Utf8SpanError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid utf-8 at byte {}", self.valid_up_to())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P: SharedOwner<str>> std::error::Error for Utf8SpanError<P> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<P: SharedOwner<str> + From<String>> Src<str, P> {
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn from_utf8(bytes: Vec<u8>) -> Result<Self, Utf8SpanError<P>> {
+        match String::from_utf8(bytes) {
+            Ok(string) => Ok(string.into()),
+            Err(err) => {
+                let error = err.utf8_error();
+                let mut bytes = err.into_bytes();
+                bytes.truncate(error.valid_up_to());
+                // just checked, and reuses the buffer instead of copying the prefix
+                let valid = unsafe { String::from_utf8_unchecked(bytes) };
+                Err(Utf8SpanError { valid: valid.into(), error })
+            },
+        }
+    }
+}
+
+impl<P: SharedOwner<str> + From<String>> Src<str, P> {
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn from_utf8_lossy(bytes: Vec<u8>) -> Self {
+        Self::from_utf8_lossy_with_replacements(bytes).0
+    }
+
+    // also hands back a span for each U+FFFD put in place of invalid bytes
+    #[cfg_attr(feature = "provenance", track_caller)]
+    pub fn from_utf8_lossy_with_replacements(bytes: Vec<u8>) -> (Self, Vec<Self>) {
+        let bytes = match String::from_utf8(bytes) {
+            Ok(string) => return (string.into(), Vec::new()),
+            Err(err) => err.into_bytes(),
+        };
+
+        let mut text = String::with_capacity(bytes.len());
+        let mut replaced = Vec::new();
+        for chunk in bytes.utf8_chunks() {
+            text.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                replaced.push(text.len()..text.len() + char::REPLACEMENT_CHARACTER.len_utf8());
+                text.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+
+        let text: Self = text.into();
+        let replaced = replaced.into_iter().map(|range| text.src_sub(range)).collect();
+        (text, replaced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn from_utf8() {
+        let a = SrcStr::<Rc<String>>::from_utf8(b"Dionysus".to_vec()).unwrap();
+        assert_eq!("Dionysus", &a[..]);
+
+        let mut bytes = b"Apollo ".to_vec();
+        bytes.extend(vec![0xff, b'!']);
+        let err = SrcStr::<Rc<String>>::from_utf8(bytes).unwrap_err();
+        assert_eq!(err.valid_up_to(), 7);
+        assert_eq!("Apollo ", &err.valid()[..]);
+        assert_eq!(err.to_string(), "invalid utf-8 at byte 7");
+    }
+
+    #[test]
+    fn lossy() {
+        let (a, replaced) = SrcStr::<Rc<String>>::from_utf8_lossy_with_replacements(b"eternal\xffrecur\xc3rence".to_vec());
+
+        assert_eq!("eternal\u{fffd}recur\u{fffd}rence", &a[..]);
+        let ranges: Vec<_> = replaced.iter().map(|r| r.range()).collect();
+        assert_eq!(ranges, [Some(7..10), Some(15..18)]);
+
+        assert_eq!(SrcStr::<Rc<String>>::from_utf8_lossy(b"return".to_vec()).range(), Some(0..6));
+    }
+}
@@ -0,0 +1,112 @@
+use core::convert::TryFrom;
+use core::ops::Deref;
+use core::str::Utf8Error;
+
+use ::bytes::Bytes;
+
+use crate::{SharedOwner, Src};
+
+// a Bytes buffer that's been checked to be utf-8
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Utf8Bytes(Bytes);
+
+impl Utf8Bytes {
+    pub fn into_inner(self) -> Bytes {
+        self.0
+    }
+}
+
+impl TryFrom<Bytes> for Utf8Bytes {
+    type Error = Utf8Error;
+    fn try_from(bytes: Bytes) -> Result<Self, Utf8Error> {
+        core::str::from_utf8(&bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl Deref for Utf8Bytes {
+    type Target = str;
+    fn deref(&self) -> &str {
+        unsafe { core::str::from_utf8_unchecked(&self.0) }
+    }
+}
+
+unsafe impl SharedOwner<str> for Utf8Bytes {
+    fn elems(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn whole(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+unsafe impl SharedOwner<[u8]> for Utf8Bytes {
+    fn elems(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn whole(&self) -> Option<&[u8]> {
+        Some(&self.0)
+    }
+}
+
+// a clone shares the same buffer, so the bytes never move
+unsafe impl SharedOwner<[u8]> for Bytes {
+    fn elems(&self) -> &[u8] {
+        self
+    }
+
+    fn whole(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+unsafe impl SharedOwner<str> for Bytes {
+    fn elems(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<Utf8Bytes> for Src<str, Utf8Bytes> {
+    fn from(bytes: Utf8Bytes) -> Self {
+        Self::whole(bytes)
+    }
+}
+
+impl From<Bytes> for Src<[u8], Bytes> {
+    fn from(bytes: Bytes) -> Self {
+        Self::whole(bytes)
+    }
+}
+
+impl Src<str, Utf8Bytes> {
+    pub fn from_bytes(bytes: Bytes) -> Result<Self, Utf8Error> {
+        Ok(Utf8Bytes::try_from(bytes)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SrcBytes;
+
+    #[test]
+    fn from_bytes() {
+        let body = Bytes::from_static(b"What does not kill me makes me stronger.");
+        let a = Src::from_bytes(body.clone()).unwrap();
+
+        assert_eq!("kill", &a.sub(14..18)[..]);
+        assert_eq!(a.as_ptr(), body.as_ptr());
+        assert!(Src::from_bytes(Bytes::from_static(b"\xff")).is_err());
+    }
+
+    #[test]
+    fn partial_text() {
+        let packet: SrcBytes<Bytes> = Bytes::from_static(b"\x02\x00stronger").into();
+        let text = packet.sub(2..10).to_srcstr().unwrap();
+
+        assert_eq!("stronger", &text[..]);
+        assert_eq!(text.range(), Some(2..10));
+    }
+}
@@ -0,0 +1,80 @@
+use core::cell::Cell;
+use alloc::rc::Rc;
+use alloc::string::String;
+
+use crate::{SourceBacking, Src};
+
+pub type VersionedSrcStr = Src<str, Rc<VersionedText>>;
+
+// one version of a document's text, remembering which version it was
+pub struct VersionedText {
+    text: String,
+    version: u64,
+    latest: Rc<Cell<u64>>,
+}
+
+unsafe impl SourceBacking for VersionedText {
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+// a document that's replaced wholesale on every change, e.g. an lsp buffer.
+// slices of older versions stay valid but report themselves stale
+pub struct VersionedSource {
+    current: VersionedSrcStr,
+    latest: Rc<Cell<u64>>,
+}
+
+impl VersionedSource {
+    pub fn new(text: impl Into<String>) -> Self {
+        let latest = Rc::new(Cell::new(0));
+        let current = Rc::new(VersionedText { text: text.into(), version: 0, latest: latest.clone() }).into();
+        Self { current, latest }
+    }
+
+    pub fn update(&mut self, text: impl Into<String>) -> VersionedSrcStr {
+        let version = self.latest.get() + 1;
+        self.latest.set(version);
+        self.current = Rc::new(VersionedText { text: text.into(), version, latest: self.latest.clone() }).into();
+        self.current.clone()
+    }
+
+    pub fn current(&self) -> &VersionedSrcStr {
+        &self.current
+    }
+
+    pub fn version(&self) -> u64 {
+        self.latest.get()
+    }
+}
+
+impl Src<str, Rc<VersionedText>> {
+    // None for 'static slices, which belong to no version
+    pub fn version(&self) -> Option<u64> {
+        self.src().map(|text| text.version)
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.src().is_some_and(|text| text.version != text.latest.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale() {
+        let mut doc = VersionedSource::new("let x = 1;");
+        let x = doc.current().sub(4..5);
+        assert_eq!((Some(0), false), (x.version(), x.is_stale()));
+
+        let current = doc.update("let y = 1;");
+        assert_eq!(1, doc.version());
+        assert_eq!("x", &x[..]);
+        assert!(x.is_stale());
+        assert!(!current.sub(4..5).is_stale());
+        assert_eq!(Some(1), current.version());
+    }
+}
@@ -0,0 +1,110 @@
+use alloc::rc::{self, Rc};
+use alloc::string::String;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::{self, Arc};
+
+use crate::provenance::Provenance;
+use crate::{Repr, SharedOwner, Slice, Src};
+
+pub trait WeakOwner<T: ?Sized + Slice>: SharedOwner<T> {
+    type Weak: Clone;
+
+    fn downgrade(&self) -> Self::Weak;
+    fn upgrade(weak: &Self::Weak) -> Option<Self>;
+}
+
+impl<T: ?Sized + Slice, X: ?Sized> WeakOwner<T> for Rc<X>
+where
+    Rc<X>: SharedOwner<T>,
+{
+    type Weak = rc::Weak<X>;
+
+    fn downgrade(&self) -> rc::Weak<X> {
+        Rc::downgrade(self)
+    }
+
+    fn upgrade(weak: &rc::Weak<X>) -> Option<Self> {
+        weak.upgrade()
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T: ?Sized + Slice, X: ?Sized> WeakOwner<T> for Arc<X>
+where
+    Arc<X>: SharedOwner<T>,
+{
+    type Weak = sync::Weak<X>;
+
+    fn downgrade(&self) -> sync::Weak<X> {
+        Arc::downgrade(self)
+    }
+
+    fn upgrade(weak: &sync::Weak<X>) -> Option<Self> {
+        weak.upgrade()
+    }
+}
+
+pub type WeakSrcStr<P = Rc<String>> = WeakSrc<str, P>;
+
+// doesn't keep the source alive; 'static slices always upgrade
+pub struct WeakSrc<T: ?Sized + Slice, P: WeakOwner<T> = Rc<<T as Slice>::Owned>> {
+    weak: Option<P::Weak>,
+    repr: Repr<T>,
+    created: Provenance,
+}
+
+impl<T: ?Sized + Slice, P: WeakOwner<T>> Clone for WeakSrc<T, P> {
+    fn clone(&self) -> Self {
+        Self { weak: self.weak.clone(), repr: self.repr, created: self.created }
+    }
+}
+
+impl<T: ?Sized + Slice, P: WeakOwner<T>> WeakSrc<T, P> {
+    pub fn upgrade(&self) -> Option<Src<T, P>> {
+        let rc = match &self.weak {
+            Some(weak) => Some(P::upgrade(weak)?),
+            None => None,
+        };
+        Some(Src { rc, repr: self.repr, created: self.created })
+    }
+
+    pub fn range(&self) -> Option<core::ops::Range<usize>> {
+        match self.repr {
+            Repr::Range { start, len } => Some(start..start + len),
+            Repr::Static(_) => None,
+        }
+    }
+}
+
+impl<T: ?Sized + Slice, P: WeakOwner<T>> Src<T, P> {
+    pub fn downgrade(&self) -> WeakSrc<T, P> {
+        WeakSrc { weak: self.rc.as_ref().map(P::downgrade), repr: self.repr, created: self.created }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SrcStr;
+
+    #[test]
+    fn upgrade() {
+        let a: SrcStr = "We have art in order not to die of the truth.".into();
+        let weak = a.sub(8..11).downgrade();
+
+        assert_eq!("art", &weak.upgrade().unwrap()[..]);
+        assert_eq!(weak.range(), Some(8..11));
+
+        drop(a);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn static_upgrade() {
+        let a = SrcStr::<Rc<String>>::from_static("truth");
+        let weak = a.downgrade();
+        drop(a);
+
+        assert_eq!("truth", &weak.upgrade().unwrap()[..]);
+    }
+}
@@ -0,0 +1,120 @@
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
+
+// implements srcstr::HasSpan as the covering span of the fields marked
+// #[span], in order. slices from different sources fall back to the first
+#[proc_macro_derive(HasSpan, attributes(span))]
+pub fn derive_has_span(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let mut types = Vec::new();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, spans) = destructure(&data.fields, &mut types);
+            let spans = spans.ok_or_else(|| syn::Error::new_spanned(name, "HasSpan needs at least one #[span] field"))?;
+            quote! {
+                let #name #pattern = self;
+                #spans
+            }
+        },
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let (pattern, spans) = destructure(&variant.fields, &mut types);
+                // every variant needs a span to give back, even unit ones
+                let spans = spans.ok_or_else(|| syn::Error::new_spanned(variant, "each variant needs at least one #[span] field"))?;
+                Ok(quote! { #name::#ident #pattern => { #spans } })
+            }).collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        },
+        Data::Union(_) => return Err(syn::Error::new_spanned(name, "HasSpan can't be derived for unions")),
+    };
+
+    let first = match types.first() {
+        Some(ty) => ty.clone(),
+        None => return Err(syn::Error::new_spanned(name, "HasSpan needs at least one #[span] field")),
+    };
+    let owner = quote! { <#first as ::srcstr::HasSpan>::Owner };
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut predicates = where_clause.map(|w| w.predicates.iter().map(|p| quote!(#p)).collect()).unwrap_or_else(Vec::new);
+    // bounds only for fields that depend on the generics; for the rest the
+    // compiler checks them anyway, and bounding a recursive type (say a
+    // Box<Self> field) on itself would never resolve
+    let params: Vec<_> = input.generics.type_params().map(|param| param.ident.to_string()).collect();
+    let generic = |ty: &Type| mentions(quote!(#ty), &params);
+    if generic(&first) {
+        predicates.push(quote! { #first: ::srcstr::HasSpan });
+    }
+    let same = |ty: &Type| quote!(#ty).to_string() == quote!(#first).to_string();
+    let rest = types[1..].iter().filter(|ty| generic(ty) && !same(ty));
+    predicates.extend(rest.map(|ty| quote! { #ty: ::srcstr::HasSpan<Owner = #owner> }));
+
+    Ok(quote! {
+        impl #impl_generics ::srcstr::HasSpan for #name #ty_generics
+        where
+            #(#predicates,)*
+        {
+            type Owner = #owner;
+
+            fn span(&self) -> ::srcstr::SrcStr<Self::Owner> {
+                #body
+            }
+        }
+    })
+}
+
+fn mentions(tokens: TokenStream2, idents: &[String]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => idents.iter().any(|i| ident == i),
+        TokenTree::Group(group) => mentions(group.stream(), idents),
+        _ => false,
+    })
+}
+
+// a pattern binding the #[span] fields, and code folding their spans (None
+// if there aren't any)
+fn destructure(fields: &Fields, types: &mut Vec<Type>) -> (TokenStream2, Option<TokenStream2>) {
+    let mut bindings = Vec::new();
+    let mut spanned = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let binding = format_ident!("__field{}", i);
+        if field.attrs.iter().any(|attr| attr.path().is_ident("span")) {
+            types.push(field.ty.clone());
+            spanned.push(binding.clone());
+        }
+        bindings.push(match &field.ident {
+            Some(ident) => quote! { #ident: #binding },
+            None => quote! { #binding },
+        });
+    }
+
+    let pattern = match fields {
+        Fields::Named(_) => quote! { { #(#bindings,)* } },
+        Fields::Unnamed(_) => quote! { ( #(#bindings,)* ) },
+        Fields::Unit => quote! {},
+    };
+    let spans = spanned.split_first().map(|(first, rest)| quote! {
+        let span = ::srcstr::HasSpan::span(#first);
+        #(
+            let next = ::srcstr::HasSpan::span(#rest);
+            let span = span.covering_span(&next).unwrap_or(span);
+        )*
+        span
+    });
+    (pattern, spans)
+}